@@ -0,0 +1,98 @@
+use crate::{AsyncTask, Cache, ExecuteError, Task, TaskOptions, DSK};
+use alloc::collections::btree_map::Entry;
+
+/// Builds a DSK from tasks inserted in any order, validating the whole graph once in `build`.
+/// Unlike `DSK::add_task`, inserting a task runs no checks at all, and may reference keys that are added later.
+pub struct DSKBuilder<'tasks, O, K: Clone = &'static str> {
+    dsk: DSK<'tasks, O, K>,
+    /// The first key inserted twice
+    duplicate: Option<K>,
+}
+
+impl<'tasks, O, K: Clone> Default for DSKBuilder<'tasks, O, K> {
+    fn default() -> Self {
+        Self {
+            dsk: DSK::new(),
+            duplicate: None,
+        }
+    }
+}
+
+impl<'tasks, O, K: Clone> DSKBuilder<'tasks, O, K> {
+    /// Generates a new, empty, DSKBuilder
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<'tasks, O, K: Ord + Clone + 'tasks> DSKBuilder<'tasks, O, K> {
+    /// Adds a task to the graph being built
+    pub fn add_task<T: Task<O, K> + 'tasks>(self, key: K, task: T) -> Self {
+        self.insert_cache(key, Cache::from(task))
+    }
+
+    /// Adds a task configured by `options`, just like `DSK::add_task_with`
+    pub fn add_task_with<T: Task<O, K> + 'tasks>(
+        self,
+        key: K,
+        task: T,
+        options: TaskOptions<'tasks, O, K>,
+    ) -> Self {
+        self.insert_cache(key, Cache::from(task).with_options(options))
+    }
+
+    /// Adds an input to the graph being built, a task whose value is set rather than computed
+    pub fn add_input(self, key: K, value: O) -> Self {
+        self.insert_cache(key, Cache::from_result(value))
+    }
+
+    /// Adds a task that can be sent across threads, just like `DSK::add_send_task`
+    pub fn add_send_task<T: Task<O, K> + Send + 'tasks>(self, key: K, task: T) -> Self {
+        self.insert_cache(key, Cache::from_send(task))
+    }
+
+    /// Adds an asynchronous task, just like `DSK::add_async_task`
+    pub fn add_async_task<T: AsyncTask<O, K> + 'tasks>(self, key: K, task: T) -> Self {
+        self.insert_cache(key, Cache::from_async(task))
+    }
+
+    /// Adds every task yielded by `tasks`
+    pub fn add_tasks<I, T>(mut self, tasks: impl IntoIterator<Item = (I, T)>) -> Self
+    where
+        I: Into<K>,
+        T: Task<O, K> + 'tasks,
+    {
+        for (key, task) in tasks {
+            self = self.add_task(key.into(), task);
+        }
+
+        self
+    }
+
+    fn insert_cache(mut self, key: K, cache: Cache<'tasks, O, K>) -> Self {
+        match self.dsk.0.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(cache);
+            }
+            Entry::Occupied(entry) => {
+                self.duplicate.get_or_insert_with(|| entry.key().clone());
+            }
+        }
+
+        self
+    }
+
+    /// Validates the whole graph, returning it if every key was inserted once,
+    /// and it has neither cycles nor missing dependencies
+    pub fn build(self) -> Result<DSK<'tasks, O, K>, ExecuteError<K>> {
+        if let Some(key) = self.duplicate {
+            return Err(ExecuteError::TaskAlreadyExists(key));
+        }
+
+        let report = self.dsk.validate();
+        match report.is_valid() {
+            true => Ok(self.dsk),
+            false => Err(ExecuteError::InvalidGraph(report)),
+        }
+    }
+}
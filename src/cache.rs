@@ -2,15 +2,66 @@ use alloc::{boxed::Box, collections::BTreeMap, fmt, format};
 
 use super::error;
 use super::Task;
-use crate::NullTask;
+use crate::{
+    options::fall_back, schedule::Slot, AsyncTask, Dependencies, NullTask, TaskOptions,
+    WithDependencies,
+};
+
+/// The boxed task backing a Cache.
+/// Tasks known to be `Send` are kept apart, so they can be handed to worker threads.
+pub(crate) enum Calculation<'task, V, K: Clone> {
+    Local(Box<dyn Task<V, K> + 'task>),
+    Send(Box<dyn Task<V, K> + Send + 'task>),
+    Async(Box<dyn AsyncTask<V, K> + 'task>),
+}
+
+impl<'task, V, K: Clone> Calculation<'task, V, K> {
+    pub(crate) fn dependencies(&self) -> Dependencies<'_, K> {
+        match self {
+            Calculation::Local(task) => task.dependencies(),
+            Calculation::Send(task) => task.dependencies(),
+            Calculation::Async(task) => task.dependencies(),
+        }
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        match self {
+            Calculation::Local(task) => task.fingerprint(),
+            Calculation::Send(task) => task.fingerprint(),
+            Calculation::Async(task) => task.fingerprint(),
+        }
+    }
+
+    fn execute(&mut self, cache: &BTreeMap<K, V>) -> Result<V, error::ExecuteError<K>> {
+        match self {
+            Calculation::Local(task) => task.execute(cache),
+            Calculation::Send(task) => task.execute(cache),
+            Calculation::Async(_) => Err(error::ExecuteError::AsyncTask),
+        }
+    }
+}
+
+/// Tracks when a Cache's result last changed, and when it was last known to be up to date.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Revisions {
+    pub(crate) changed_at: u64,
+    pub(crate) verified_at: u64,
+    pub(crate) input: bool,
+    /// How many times the task ran when it was last brought up to date, 0 if its cached result was reused
+    pub(crate) attempts: u32,
+    /// Whether the cached result came from the task's fallback, rather than the task itself
+    pub(crate) fallback: bool,
+}
 
 /// A Cache is a task that once executed, caches it's result
-pub struct Cache<'task, V> {
-    result: Option<V>,
-    calculation: Box<dyn Task<V> + 'task>,
+pub struct Cache<'task, V, K: Clone = &'static str> {
+    pub(crate) result: Option<V>,
+    calculation: Calculation<'task, V, K>,
+    pub(crate) revisions: Revisions,
+    pub(crate) options: TaskOptions<'task, V, K>,
 }
 
-impl<'task, V: fmt::Debug> fmt::Debug for Cache<'task, V> {
+impl<'task, V: fmt::Debug, K: Clone> fmt::Debug for Cache<'task, V, K> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Cache")
             .field("result", &self.result)
@@ -22,52 +73,225 @@ impl<'task, V: fmt::Debug> fmt::Debug for Cache<'task, V> {
     }
 }
 
-impl<'task, V> Cache<'task, V> {
+impl<'task, V, K: Clone + 'task> Cache<'task, V, K> {
     /// Create a new Cache from the given task.
-    pub fn from(task: impl Task<V> + 'task) -> Self {
+    pub fn from(task: impl Task<V, K> + 'task) -> Self {
         Self {
             result: None,
-            calculation: Box::new(task),
+            revisions: Revisions::default(),
+            options: TaskOptions::default(),
+            calculation: Calculation::Local(Box::new(task)),
+        }
+    }
+
+    /// Create a new Cache from a task that can be sent across threads.
+    pub fn from_send(task: impl Task<V, K> + Send + 'task) -> Self {
+        Self {
+            result: None,
+            revisions: Revisions::default(),
+            options: TaskOptions::default(),
+            calculation: Calculation::Send(Box::new(task)),
+        }
+    }
+
+    /// Create a new Cache from an asynchronous task.
+    /// Such a Cache can only be computed by `DSK::execute_async`.
+    pub fn from_async(task: impl AsyncTask<V, K> + 'task) -> Self {
+        Self {
+            result: None,
+            revisions: Revisions::default(),
+            options: TaskOptions::default(),
+            calculation: Calculation::Async(Box::new(task)),
         }
     }
 
     /// Create a new Cache with a pre-defined result.
-    pub fn from_result(result: V) -> Cache<'task, V> {
+    pub fn from_result(result: V) -> Cache<'task, V, K> {
         Cache {
             result: Some(result),
-            calculation: Box::new(NullTask),
+            calculation: Calculation::Send(Box::new(NullTask)),
+            revisions: Revisions {
+                input: true,
+                ..Revisions::default()
+            },
+            options: TaskOptions::default(),
         }
     }
 
     /// Create a new Cache using a closure.
-    pub fn from_closure<F: Fn(&BTreeMap<&str, V>) -> V + 'task>(f: F) -> Self {
+    pub fn from_closure<F: Fn(&BTreeMap<K, V>) -> V + 'task>(f: F) -> Self {
         Self {
             result: None,
-            calculation: Box::new(f),
+            revisions: Revisions::default(),
+            options: TaskOptions::default(),
+            calculation: Calculation::Local(Box::new(f)),
         }
     }
 
+    /// Create a new Cache using a closure that depends on the given keys.
+    pub fn from_closure_with_dependencies<D, F>(
+        dependencies: impl IntoIterator<Item = D>,
+        f: F,
+    ) -> Self
+    where
+        D: Into<K>,
+        F: Fn(&BTreeMap<K, V>) -> V + 'task,
+    {
+        Self::from(WithDependencies::new(dependencies, f))
+    }
+
+    /// Configure how this Cache's task is executed.
+    pub fn with_options(mut self, options: TaskOptions<'task, V, K>) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Runs the task according to its options, recording how many attempts it took.
+    fn run(&mut self, cache: &BTreeMap<K, V>) -> Result<V, error::ExecuteError<K>> {
+        let calculation = &mut self.calculation;
+        let (output, attempts) = self.options.retry.run(|| calculation.execute(cache));
+        self.revisions.attempts = attempts;
+        output
+    }
+
     /// Get a mutable reference to the task's output.
-    pub fn get(&mut self, cache: &BTreeMap<&str, V>) -> Result<&mut V, error::ExecuteError> {
+    pub fn get(&mut self, cache: &BTreeMap<K, V>) -> Result<&mut V, error::ExecuteError<K>> {
         match self.result {
             Some(ref mut res) => Ok(res),
             None => {
-                let v = self.calculation.execute(cache)?;
+                let v = self.run(cache)?;
                 Ok(self.result.get_or_insert(v))
             }
         }
     }
 
     /// Execute the task and return the result.
-    pub fn consume(mut self, cache: &BTreeMap<&str, V>) -> Result<V, error::ExecuteError> {
+    pub fn consume(mut self, cache: &BTreeMap<K, V>) -> Result<V, error::ExecuteError<K>> {
         match self.result {
             Some(res) => Ok(res),
-            None => self.calculation.execute(cache),
+            None => self.run(cache),
         }
     }
 
+    /// Whether this task's result has already been computed and cached.
+    pub fn is_computed(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Clear the cached result, so the task is recomputed on its next execution.
+    /// A Cache created with `from_result` has nothing to recompute from, and will error instead.
+    pub fn reset(&mut self) -> Option<V> {
+        self.result.take()
+    }
+
+    /// Whether this Cache is an input, holding a result that is set rather than computed.
+    pub fn is_input(&self) -> bool {
+        self.revisions.input
+    }
+
+    /// Replaces an input's, or a restored, result, recording that it changed at `revision`.
+    pub(crate) fn set_input(&mut self, result: V, revision: u64) {
+        self.result = Some(result);
+        self.revisions.changed_at = revision;
+        self.revisions.verified_at = revision;
+        self.revisions.fallback = false;
+    }
+
+    /// Whether the cached result is up to date, given when its dependencies last changed.
+    /// A result from the task's fallback never is, so that the task itself is tried again.
+    pub(crate) fn is_fresh(&self, dependencies_changed_at: u64) -> bool {
+        self.result.is_some()
+            && !self.revisions.fallback
+            && (self.revisions.input || self.revisions.verified_at >= dependencies_changed_at)
+    }
+
     /// Get this task's dependencies.
-    pub fn dependencies(&self) -> &'static [&'static str] {
+    pub fn dependencies(&self) -> Dependencies<'_, K> {
         self.calculation.dependencies()
     }
+
+    /// Get this task's fingerprint, if it has one.
+    pub fn fingerprint(&self) -> Option<u64> {
+        self.calculation.fingerprint()
+    }
+
+    /// Decomposes this Cache into its result, the task that computes it, its revisions and options.
+    pub(crate) fn into_parts(
+        self,
+    ) -> (
+        Option<V>,
+        Calculation<'task, V, K>,
+        Revisions,
+        TaskOptions<'task, V, K>,
+    ) {
+        (self.result, self.calculation, self.revisions, self.options)
+    }
+
+    /// Reassembles a Cache decomposed by `into_parts`.
+    pub(crate) fn from_parts(
+        result: Option<V>,
+        calculation: Calculation<'task, V, K>,
+        revisions: Revisions,
+        options: TaskOptions<'task, V, K>,
+    ) -> Self {
+        Self {
+            result,
+            calculation,
+            revisions,
+            options,
+        }
+    }
+
+    /// Splits this Cache into its result slot, the task that computes it, its revisions and options.
+    pub(crate) fn split_mut(&mut self) -> Slot<'_, 'task, V, K> {
+        Slot {
+            result: &mut self.result,
+            calculation: &mut self.calculation,
+            revisions: &mut self.revisions,
+            retry: &self.options.retry,
+            fallback: &mut self.options.fallback,
+        }
+    }
+}
+
+impl<'task, V: Clone, K: Clone + 'task> Cache<'task, V, K> {
+    /// Brings the cached result up to date at `revision`, recomputing it only if a dependency changed after
+    /// it was last verified. A recomputed result that `same` deems equal to the old one keeps its revision.
+    /// A task that fails is replaced by its fallback, if it has one.
+    pub(crate) fn refresh(
+        &mut self,
+        cache: &BTreeMap<K, V>,
+        revision: u64,
+        dependencies_changed_at: u64,
+        same: impl Fn(&V, &V) -> bool,
+    ) -> Result<&mut V, error::ExecuteError<K>> {
+        self.revisions.attempts = 0;
+        if !self.is_fresh(dependencies_changed_at) {
+            let output = self.run(cache);
+            let v = fall_back(
+                &mut self.options.fallback,
+                &mut self.revisions,
+                cache,
+                output,
+            )?;
+            if !self.result.as_ref().is_some_and(|old| same(old, &v)) {
+                self.revisions.changed_at = revision;
+            }
+            self.result = Some(v);
+        }
+
+        // A fallback's result stands in for the task's until it's tried again, rather than being verified
+        if !self.revisions.fallback {
+            self.revisions.verified_at = revision;
+        }
+        Ok(self.result.as_mut().unwrap())
+    }
+
+    /// Computes a result without caching it. An input's result is its value, there's nothing to compute.
+    pub(crate) fn compute(&mut self, cache: &BTreeMap<K, V>) -> Result<V, error::ExecuteError<K>> {
+        match &self.result {
+            Some(result) if self.revisions.input => Ok(result.clone()),
+            _ => self.run(cache),
+        }
+    }
 }
@@ -0,0 +1,237 @@
+use crate::{
+    cache::Calculation, AsyncTask, Cache, Dependencies, ExecuteError, Fallback, Task, TaskFuture,
+    TaskOptions, WithDependencies, DSK,
+};
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    format,
+    string::String,
+    vec::Vec,
+};
+use core::marker::PhantomData;
+
+/// What `DSK::merge` should do when both DSKs hold a task under the same key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnConflict {
+    /// Fail, merging nothing
+    Fail,
+    /// Keep the task already in the DSK being merged into
+    PreferLeft,
+    /// Take the task from the DSK being merged in
+    PreferRight,
+}
+
+impl<'tasks, O, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
+    /// Moves every task of `other` into this DSK, resolving duplicate keys according to `on_conflict`.
+    /// Nothing is merged if that would close a cycle.
+    /// Tasks depending on a task replaced under `OnConflict::PreferRight` are invalidated,
+    /// as are the tasks of `other` depending on its own task under a key kept by `OnConflict::PreferLeft`
+    pub fn merge(
+        &mut self,
+        other: DSK<'tasks, O, K>,
+        on_conflict: OnConflict,
+    ) -> Result<(), ExecuteError<K>> {
+        if on_conflict == OnConflict::Fail {
+            if let Some(key) = other.0.keys().find(|key| self.0.contains_key(*key)) {
+                return Err(ExecuteError::TaskAlreadyExists(key.clone()));
+            }
+        }
+
+        let mut inserted = BTreeSet::new();
+        let mut replaced = Vec::new();
+        let mut kept = BTreeSet::new();
+        for (key, cache) in other.0 {
+            match self.0.entry(key) {
+                Entry::Vacant(entry) => {
+                    inserted.insert(entry.key().clone());
+                    entry.insert(cache);
+                }
+                Entry::Occupied(mut entry) => {
+                    if on_conflict == OnConflict::PreferRight {
+                        replaced.push((entry.key().clone(), entry.insert(cache)));
+                    } else {
+                        kept.insert(entry.key().clone());
+                    }
+                }
+            }
+        }
+
+        if let Some(cycle) = self.validate().cycles.into_iter().next() {
+            for key in inserted {
+                self.0.remove(&key);
+            }
+            for (key, cache) in replaced {
+                self.0.insert(key, cache);
+            }
+            return Err(ExecuteError::CyclicDependency(cycle));
+        }
+
+        // Results computed from a task that was just thrown away are stale: those of the tasks depending on a
+        // replaced task, and those of the tasks of `other` depending on its own copy of a task that was kept
+        let replaced = replaced
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect::<BTreeSet<_>>();
+        let stale = self
+            .0
+            .iter()
+            .filter(|(key, task)| {
+                task.dependencies().iter().any(|dep| {
+                    replaced.contains(dep) || (kept.contains(dep) && inserted.contains(*key))
+                })
+            })
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        self.invalidate_many(stale);
+
+        self.1 = self.1.max(other.1);
+        Ok(())
+    }
+}
+
+impl<'tasks, O: Clone + 'tasks> DSK<'tasks, O, String> {
+    /// Moves every task of `other` into this DSK, namespaced under `prefix`.
+    /// A key `"load"` of `other` becomes `"{prefix}/load"`, as do its dependents' dependencies on it.
+    /// A dependency starting with `/` refers to a key outside the namespace: `"/raw"` depends on `"raw"`.
+    /// Nothing is mounted if any namespaced key is already taken, or if that would close a cycle
+    pub fn mount<K>(
+        &mut self,
+        prefix: &str,
+        other: DSK<'tasks, O, K>,
+    ) -> Result<(), ExecuteError<String>>
+    where
+        K: Ord + Clone + AsRef<str> + 'tasks,
+    {
+        let tasks = other.0.into_iter().map(|(key, cache)| {
+            (
+                namespaced(prefix, key.as_ref()),
+                mounted(prefix, cache, None),
+            )
+        });
+
+        self.merge(DSK(tasks.collect(), other.1), OnConflict::Fail)
+    }
+}
+
+/// A Cache of a mounted DSK, whose task, and fallback task, now use namespaced keys.
+/// A fallback task is given the outputs of `reads`, its failed task's dependencies, rather than its own.
+fn mounted<'tasks, O, K>(
+    prefix: &str,
+    cache: Cache<'tasks, O, K>,
+    reads: Option<Vec<K>>,
+) -> Cache<'tasks, O, String>
+where
+    O: Clone + 'tasks,
+    K: Ord + Clone + AsRef<str> + 'tasks,
+{
+    let (result, calculation, revisions, options) = cache.into_parts();
+    let dependencies = calculation.dependencies().into_owned();
+    let calculation = match (calculation, reads) {
+        (Calculation::Local(task), Some(reads)) => Calculation::Local(Box::new(Mounted::new(
+            prefix,
+            WithDependencies::new(reads, task),
+        ))),
+        (Calculation::Local(task), None) => {
+            Calculation::Local(Box::new(Mounted::new(prefix, task)))
+        }
+        // Fallbacks only ever run local tasks, the others never read their inputs
+        (Calculation::Send(task), _) => Calculation::Send(Box::new(Mounted::new(prefix, task))),
+        (Calculation::Async(task), _) => Calculation::Async(Box::new(Mounted::new(prefix, task))),
+    };
+
+    let options = TaskOptions {
+        retry: options.retry,
+        fallback: options.fallback.map(|fallback| {
+            let cache = mounted(prefix, fallback.into_cache(), Some(dependencies));
+            Fallback::from_cache(cache)
+        }),
+    };
+    Cache::from_parts(result, calculation, revisions, options)
+}
+
+/// The key `key`, written inside the namespace `prefix`, refers to.
+fn namespaced(prefix: &str, key: &str) -> String {
+    match key.strip_prefix('/') {
+        Some(key) => String::from(key),
+        None => format!("{prefix}/{key}"),
+    }
+}
+
+/// A task of a mounted DSK, translating between its own keys and namespaced ones.
+struct Mounted<K, T> {
+    prefix: String,
+    task: T,
+    _keys: PhantomData<fn() -> K>,
+}
+
+impl<K: Ord + Clone + AsRef<str>, T> Mounted<K, T> {
+    fn new(prefix: &str, task: T) -> Self {
+        Self {
+            prefix: String::from(prefix),
+            task,
+            _keys: PhantomData,
+        }
+    }
+
+    /// The namespaced outputs of `dependencies`, under their original keys.
+    fn inputs<O: Clone>(&self, dependencies: &[K], cache: &BTreeMap<String, O>) -> BTreeMap<K, O> {
+        dependencies
+            .iter()
+            .filter_map(|dep| {
+                let output = cache.get(&namespaced(&self.prefix, dep.as_ref()))?;
+                Some((dep.clone(), output.clone()))
+            })
+            .collect()
+    }
+
+    fn namespace_dependencies(&self, dependencies: &[K]) -> Dependencies<'_, String> {
+        Cow::Owned(
+            dependencies
+                .iter()
+                .map(|dep| namespaced(&self.prefix, dep.as_ref()))
+                .collect(),
+        )
+    }
+}
+
+impl<O: Clone, K: Ord + Clone + AsRef<str>, T: Task<O, K>> Task<O, String> for Mounted<K, T> {
+    fn execute(&mut self, cache: &BTreeMap<String, O>) -> Result<O, ExecuteError<String>> {
+        let inputs = self.inputs(&self.task.dependencies(), cache);
+        self.task
+            .execute(&inputs)
+            .map_err(|err| err.map_keys(|key| namespaced(&self.prefix, key.as_ref())))
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, String> {
+        self.namespace_dependencies(&self.task.dependencies())
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        self.task.fingerprint()
+    }
+}
+
+impl<O: Clone, K: Ord + Clone + AsRef<str>, T: AsyncTask<O, K>> AsyncTask<O, String>
+    for Mounted<K, T>
+{
+    fn execute<'a>(&'a mut self, cache: &'a BTreeMap<String, O>) -> TaskFuture<'a, O, String> {
+        let inputs = self.inputs(&self.task.dependencies(), cache);
+        Box::pin(async move {
+            let prefix = &self.prefix;
+            self.task
+                .execute(&inputs)
+                .await
+                .map_err(|err| err.map_keys(|key| namespaced(prefix, key.as_ref())))
+        })
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, String> {
+        self.namespace_dependencies(&self.task.dependencies())
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        self.task.fingerprint()
+    }
+}
@@ -1,65 +1,154 @@
-use crate::{Cache, ExecuteError, Task};
+use crate::{AsyncTask, Cache, DSKBuilder, ExecuteError, Task, TaskOptions};
 use alloc::{
+    borrow::Borrow,
     collections::{BTreeMap, BTreeSet},
-    string::ToString,
+    vec,
     vec::Vec,
 };
+use core::fmt;
 
 /// A DSK is a directed acyclic graph of Tasks.
 /// It is used to execute a series of tasks in a specific order.
 /// The order is determined by the dependencies of each task.
+/// Tasks are identified by keys of type `K`, which defaults to `&'static str`, like `Task`'s.
+/// Keys that don't live that long, e.g. built at runtime, can be owned `String`s instead.
 #[derive(Debug)]
-pub struct DSK<'tasks, O>(pub(crate) BTreeMap<&'tasks str, Cache<'tasks, O>>);
+pub struct DSK<'tasks, O, K: Clone = &'static str>(
+    pub(crate) BTreeMap<K, Cache<'tasks, O, K>>,
+    /// The current revision, bumped every time an input is set
+    pub(crate) u64,
+);
 
-impl<'tasks, O> DSK<'tasks, O> {
+/// What `DSK::remove_task` should do when other tasks depend on the removed one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnDependents {
+    /// Fail, removing nothing
+    Fail,
+    /// Remove the task anyway, leaving its dependents with a missing dependency
+    Break,
+}
+
+impl<'tasks, O, K: Clone> Default for DSK<'tasks, O, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'tasks, O, K: Clone> DSK<'tasks, O, K> {
     /// Generates a new DSK
     pub fn new() -> Self {
-        Self(BTreeMap::new())
+        Self(BTreeMap::new(), 0)
     }
+}
 
+impl<'tasks, O, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
     /// Adds a task to the DSK
-    pub fn add_task<T: Task<O> + 'tasks>(
+    pub fn add_task<T: Task<O, K> + 'tasks>(
+        &mut self,
+        key: K,
+        task: T,
+    ) -> Result<(), ExecuteError<K>> {
+        self.insert_cache(key, Cache::from(task))
+    }
+
+    /// Adds a task to the DSK, configured by `options`, e.g. to retry it when it fails
+    pub fn add_task_with<T: Task<O, K> + 'tasks>(
+        &mut self,
+        key: K,
+        task: T,
+        options: TaskOptions<'tasks, O, K>,
+    ) -> Result<(), ExecuteError<K>> {
+        self.insert_cache(key, Cache::from(task).with_options(options))
+    }
+
+    /// Replaces the options of the task behind `key`, whichever way it was added
+    pub fn set_options(
+        &mut self,
+        key: K,
+        options: TaskOptions<'tasks, O, K>,
+    ) -> Result<(), ExecuteError<K>> {
+        match self.0.get_mut(&key) {
+            Some(cache) => {
+                cache.options = options;
+                Ok(())
+            }
+            None => Err(ExecuteError::MissingDependency(key)),
+        }
+    }
+
+    /// Adds an input to the DSK, a task whose value is set rather than computed.
+    /// Its value can later be replaced through `set_input`
+    pub fn add_input(&mut self, key: K, value: O) -> Result<(), ExecuteError<K>> {
+        let mut cache = Cache::from_result(value);
+        cache.revisions.changed_at = self.1;
+        self.insert_cache(key, cache)
+    }
+
+    /// Replaces the value of an input, starting a new revision.
+    /// Tasks depending on it are recomputed by the next `execute` or `execute_incremental`
+    pub fn set_input(&mut self, key: K, value: O) -> Result<(), ExecuteError<K>> {
+        match self.0.get_mut(&key) {
+            Some(cache) if cache.is_input() => {
+                self.1 += 1;
+                cache.set_input(value, self.1);
+                Ok(())
+            }
+            Some(_) => Err(ExecuteError::NotAnInput(key)),
+            None => Err(ExecuteError::MissingDependency(key)),
+        }
+    }
+
+    /// The current revision of the DSK, bumped every time an input is set.
+    pub fn revision(&self) -> u64 {
+        self.1
+    }
+
+    /// Adds a task that can be sent across threads.
+    /// Unlike tasks added through `add_task`, the parallel executor can run these on its worker threads
+    pub fn add_send_task<T: Task<O, K> + Send + 'tasks>(
+        &mut self,
+        key: K,
+        task: T,
+    ) -> Result<(), ExecuteError<K>> {
+        self.insert_cache(key, Cache::from_send(task))
+    }
+
+    /// Adds an asynchronous task to the DSK.
+    /// Tasks depending on it can only be resolved by `execute_async`
+    pub fn add_async_task<T: AsyncTask<O, K> + 'tasks>(
         &mut self,
-        key: &'tasks str,
+        key: K,
         task: T,
-    ) -> Result<(), ExecuteError> {
-        if self.0.contains_key(key) {
-            return Err(ExecuteError::TaskAlreadyExists(key.to_string()));
+    ) -> Result<(), ExecuteError<K>> {
+        self.insert_cache(key, Cache::from_async(task))
+    }
+
+    fn insert_cache(&mut self, key: K, cache: Cache<'tasks, O, K>) -> Result<(), ExecuteError<K>> {
+        if self.0.contains_key(&key) {
+            return Err(ExecuteError::TaskAlreadyExists(key));
         } else {
-            self.0.insert(key, Cache::from(task));
+            self.0.insert(key.clone(), cache);
         }
 
-        self.check_cyclic_dependencies(key)
+        self.check_cyclic_dependencies(&key)
     }
 
     /// Culls any tasks that are not useful in resolving the provided tasks
-    pub fn cull(mut self, keys: &[&str]) -> Result<DSK<'tasks, O>, ExecuteError> {
+    pub fn cull(mut self, keys: &[K]) -> Result<DSK<'tasks, O, K>, ExecuteError<K>> {
         let mut required = BTreeSet::new();
+        // Dependencies are pushed in reverse, so they're read in the order they're declared
+        let mut stack = keys.iter().rev().cloned().collect::<Vec<_>>();
 
-        fn read_dependencies<'tasks, O>(
-            dsk: &DSK<'tasks, O>,
-            key: &'tasks str,
-            required: &mut BTreeSet<&'tasks str>,
-        ) -> Result<(), ExecuteError> {
-            if required.contains(key) {
-                return Ok(());
+        while let Some(key) = stack.pop() {
+            if required.contains(&key) {
+                continue;
             }
 
-            match dsk.0.get(key) {
-                Some(task) => {
-                    required.insert(key);
-                    for dep in task.dependencies() {
-                        read_dependencies(dsk, dep, required)?;
-                    }
-                }
-                None => return Err(ExecuteError::MissingDependency(key.to_string())),
+            match self.0.get(&key) {
+                Some(task) => stack.extend(task.dependencies().iter().rev().cloned()),
+                None => return Err(ExecuteError::MissingDependency(key)),
             }
-
-            Ok(())
-        }
-
-        for task in keys {
-            read_dependencies(&self, task, &mut required)?;
+            required.insert(key);
         }
 
         self.0.retain(|key, _| required.contains(key));
@@ -67,10 +156,10 @@ impl<'tasks, O> DSK<'tasks, O> {
     }
 
     /// Returns a Set of all the keys in the DSK, that are also in the slice provided
-    pub fn keys_in_dsk(&self, tasks: &[&dyn Task<O>]) -> BTreeSet<&'tasks str> {
+    pub fn keys_in_dsk(&self, tasks: &[&dyn Task<O, K>]) -> BTreeSet<K> {
         let tasks_iter = tasks
-            .into_iter()
-            .flat_map(|t| t.dependencies().into_iter())
+            .iter()
+            .flat_map(|t| t.dependencies().into_owned())
             .collect::<BTreeSet<_>>();
         self.0
             .keys()
@@ -80,84 +169,495 @@ impl<'tasks, O> DSK<'tasks, O> {
     }
 
     /// Gives us a Set of direct dependencies
-    pub fn get_dependents(&self, leaf: &str) -> BTreeSet<&'tasks str> {
+    pub fn get_dependents<Q>(&self, leaf: &Q) -> BTreeSet<K>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         self.0
             .iter()
-            .filter(|(_, task)| task.dependencies().contains(&leaf))
-            .map(|(name, _)| *name)
+            .filter(|(_, task)| task.dependencies().iter().any(|dep| dep.borrow() == leaf))
+            .map(|(name, _)| name.clone())
             .collect()
     }
 
-    /// This checks for cyclic dependencies in the graph during task insertion.
-    pub fn check_cyclic_dependencies(&self, root: &str) -> Result<(), ExecuteError> {
+    /// Clears the cached result of `key` and of every task that transitively depends on it.
+    /// Inputs keep their value, they have nothing to recompute it from, but still invalidate their dependents.
+    /// Returns the keys of the invalidated tasks, which are recomputed by the next `execute`
+    pub fn invalidate<Q>(&mut self, key: &Q) -> BTreeSet<K>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.0.get_key_value(key) {
+            Some((key, _)) => self.invalidate_many([key.clone()]),
+            None => BTreeSet::new(),
+        }
+    }
+
+    /// Invalidates like `invalidate`, starting from every one of `keys`.
+    /// The dependents of every task are gathered once up front, rather than searched for at every step
+    pub(crate) fn invalidate_many(&mut self, keys: impl IntoIterator<Item = K>) -> BTreeSet<K> {
+        let mut dependents = BTreeMap::<K, Vec<K>>::new();
+        for (key, task) in self.0.iter() {
+            for dep in task.dependencies().iter() {
+                dependents.entry(dep.clone()).or_default().push(key.clone());
+            }
+        }
+
+        let mut invalidated = BTreeSet::new();
         let mut visited = BTreeSet::new();
-        let mut stack = Vec::with_capacity(self.0.len() / 2);
+        let mut stack = keys.into_iter().collect::<Vec<_>>();
+        while let Some(key) = stack.pop() {
+            if !visited.insert(key.clone()) {
+                continue;
+            }
+
+            if let Some(dependents) = dependents.remove(&key) {
+                stack.extend(dependents);
+            }
+            match self.0.get_mut(&key) {
+                Some(task) if !task.is_input() => {
+                    task.reset();
+                    invalidated.insert(key);
+                }
+                _ => {}
+            }
+        }
+
+        invalidated
+    }
+
+    /// Removes the task behind `key`, returning the tasks depending on it, which now have a missing dependency.
+    /// Their cached results are invalidated, so a task later added under `key` is used to recompute them.
+    /// With `OnDependents::Fail`, nothing is removed if any task depends on `key`
+    pub fn remove_task(
+        &mut self,
+        key: K,
+        on_dependents: OnDependents,
+    ) -> Result<BTreeSet<K>, ExecuteError<K>> {
+        if !self.0.contains_key(&key) {
+            return Err(ExecuteError::MissingDependency(key));
+        }
+
+        let dependents = self.get_dependents::<K>(&key);
+        if !dependents.is_empty() && on_dependents == OnDependents::Fail {
+            return Err(ExecuteError::HasDependents(
+                key,
+                dependents.into_iter().collect(),
+            ));
+        }
+
+        self.0.remove(&key);
+        self.invalidate_many(dependents.iter().cloned());
+        Ok(dependents)
+    }
+
+    /// Replaces the task behind `key`, invalidating its cached result and that of every task depending on it.
+    /// The task keeps the options it was added with, but becomes a local task, just like with `add_task`.
+    /// Returns the keys of the invalidated tasks. If the new task would close a cycle, the old one is kept
+    pub fn replace_task<T: Task<O, K> + 'tasks>(
+        &mut self,
+        key: K,
+        task: T,
+    ) -> Result<BTreeSet<K>, ExecuteError<K>> {
+        self.replace_cache(key, Cache::from(task), true)
+    }
+
+    /// Replaces the task behind `key` like `replace_task`, configuring the new task by `options` instead
+    pub fn replace_task_with<T: Task<O, K> + 'tasks>(
+        &mut self,
+        key: K,
+        task: T,
+        options: TaskOptions<'tasks, O, K>,
+    ) -> Result<BTreeSet<K>, ExecuteError<K>> {
+        self.replace_cache(key, Cache::from(task).with_options(options), false)
+    }
+
+    /// Replaces the task behind `key` like `replace_task`, by one that can be sent across threads
+    pub fn replace_send_task<T: Task<O, K> + Send + 'tasks>(
+        &mut self,
+        key: K,
+        task: T,
+    ) -> Result<BTreeSet<K>, ExecuteError<K>> {
+        self.replace_cache(key, Cache::from_send(task), true)
+    }
+
+    /// Replaces the task behind `key` like `replace_task`, by an asynchronous one
+    pub fn replace_async_task<T: AsyncTask<O, K> + 'tasks>(
+        &mut self,
+        key: K,
+        task: T,
+    ) -> Result<BTreeSet<K>, ExecuteError<K>> {
+        self.replace_cache(key, Cache::from_async(task), true)
+    }
+
+    fn replace_cache(
+        &mut self,
+        key: K,
+        mut cache: Cache<'tasks, O, K>,
+        keep_options: bool,
+    ) -> Result<BTreeSet<K>, ExecuteError<K>> {
+        let mut old = match self.0.get_mut(&key) {
+            Some(old) => {
+                if keep_options {
+                    core::mem::swap(&mut cache.options, &mut old.options);
+                }
+                core::mem::replace(old, cache)
+            }
+            None => return Err(ExecuteError::MissingDependency(key)),
+        };
+
+        if let Err(err) = self.check_cyclic_dependencies::<K>(&key) {
+            let new = self.0.get_mut(&key).unwrap();
+            if keep_options {
+                core::mem::swap(&mut new.options, &mut old.options);
+            }
+            *new = old;
+            return Err(err);
+        }
+
+        Ok(self.invalidate::<K>(&key))
+    }
+
+    /// Moves the task behind `from` to `to`, returning the tasks that depended on `from`.
+    /// Tasks declare their dependencies themselves, so those are now missing a dependency, and are invalidated,
+    /// while tasks already depending on `to` now depend on the moved task. Nothing is moved if that closes a cycle
+    pub fn rename_task(&mut self, from: K, to: K) -> Result<BTreeSet<K>, ExecuteError<K>> {
+        if self.0.contains_key(&to) {
+            return Err(ExecuteError::TaskAlreadyExists(to));
+        }
+
+        let task = match self.0.remove(&from) {
+            Some(task) => task,
+            None => return Err(ExecuteError::MissingDependency(from)),
+        };
+
+        self.0.insert(to.clone(), task);
+        if let Err(err) = self.check_cyclic_dependencies::<K>(&to) {
+            let task = self.0.remove(&to).unwrap();
+            self.0.insert(from, task);
+            return Err(err);
+        }
+
+        // Tasks that were missing `to` may hold results computed before it went missing
+        let broken = self.get_dependents::<K>(&from);
+        self.invalidate_many(
+            self.get_dependents::<K>(&to)
+                .into_iter()
+                .chain(broken.iter().cloned()),
+        );
+
+        Ok(broken)
+    }
+
+    /// This checks for cyclic dependencies in the graph during task insertion.
+    pub fn check_cyclic_dependencies<Q>(&self, root: &Q) -> Result<(), ExecuteError<K>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let Some((root, task)) = self.0.get_key_value(root) else {
+            return Ok(());
+        };
 
-        fn find_cycles<'root, O>(
-            dsk: &DSK<'root, O>,
-            root: &'root str,
-            visited: &mut BTreeSet<&'root str>,
-            stack: &mut Vec<&'root str>,
-        ) -> Result<(), ExecuteError> {
-            stack.push(root);
+        // The path from the root to the task being visited, along with how many of each task's dependencies were
+        // visited so far. Every task on it depends on the next one, so reaching any of them again closes a cycle
+        let mut path = vec![(root, task.dependencies(), 0)];
+        let mut on_path = BTreeSet::from([root]);
+        let mut done = BTreeSet::new();
 
-            if visited.contains(root) {
-                return Err(ExecuteError::CyclicDependency(
-                    stack.into_iter().map(|f| f.to_string()).collect(),
-                ));
+        while let Some((key, dependencies, visited)) = path.last_mut() {
+            let key = *key;
+            let Some(dep) = dependencies.get(*visited) else {
+                path.pop();
+                on_path.remove(key);
+                done.insert(key);
+                continue;
+            };
+            *visited += 1;
+
+            // A missing dependency has no dependencies of its own, so it cannot close a cycle
+            let Some((dep, task)) = self.0.get_key_value::<K>(dep) else {
+                continue;
+            };
+
+            if on_path.contains(dep) {
+                let mut cycle = path
+                    .iter()
+                    .map(|(key, ..)| (*key).clone())
+                    .collect::<Vec<_>>();
+                cycle.push(dep.clone());
+                return Err(ExecuteError::CyclicDependency(cycle));
             }
 
-            visited.insert(root);
+            if !done.contains(dep) {
+                path.push((dep, task.dependencies(), 0));
+                on_path.insert(dep);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The shortest chain of dependencies leading from `from` to `to`, both included.
+    /// `to` may be a missing dependency. Empty if `from` doesn't depend on `to`
+    pub(crate) fn dependency_path(&self, from: &K, to: &K) -> Vec<K> {
+        let mut parents = BTreeMap::from([(from, from)]);
+        let mut queue = alloc::collections::VecDeque::from([from]);
 
-            if let Some(t) = dsk.0.get(root) {
-                for dep in t.dependencies() {
-                    find_cycles(dsk, dep, visited, stack)?;
-                    visited.clear();
+        while let Some(key) = queue.pop_front() {
+            if key == to {
+                let mut path = vec![key.clone()];
+                let mut key = key;
+                while key != from {
+                    key = parents[key];
+                    path.push(key.clone());
                 }
+                path.reverse();
+                return path;
             }
 
-            stack.pop();
+            let Some((key, task)) = self.0.get_key_value(key) else {
+                continue;
+            };
+            for dep in task.dependencies().iter() {
+                // A missing dependency isn't a key of the map, `to` stands in for it
+                let dep = match self.0.get_key_value(dep) {
+                    Some((dep, _)) => dep,
+                    None if dep == to => to,
+                    None => continue,
+                };
 
-            Ok(())
+                if !parents.contains_key(dep) {
+                    parents.insert(dep, key);
+                    queue.push_back(dep);
+                }
+            }
         }
 
-        find_cycles(self, root, &mut visited, &mut stack)
+        Vec::new()
+    }
+
+    /// Lists the tasks needed by `roots` in the order `execute` would run them, one root after the other.
+    /// Stops at the first missing key, returning the error `execute` would alongside the order:
+    /// a missing dependency comes with the path leading from its root to the task declaring it.
+    pub(crate) fn execution_order(
+        &self,
+        roots: impl IntoIterator<Item = K>,
+    ) -> (Vec<K>, Option<ExecuteError<K>>) {
+        let (order, mut missing) = self.ordered(roots, false);
+        (order, missing.pop().map(|(_, err)| err))
+    }
+
+    /// Lists the tasks needed by `roots` like `execution_order`, along with the error of every task failing
+    /// because of a missing key: a missing root, or the task declaring a missing dependency.
+    /// Unless `keep_going`, stops at the first of them.
+    pub(crate) fn ordered(
+        &self,
+        roots: impl IntoIterator<Item = K>,
+        keep_going: bool,
+    ) -> (Vec<K>, Vec<(K, ExecuteError<K>)>) {
+        let mut order = Vec::new();
+        let mut missing = Vec::new();
+        let mut seen = BTreeSet::new();
+
+        for root in roots {
+            if seen.contains(&root) {
+                continue;
+            }
+
+            let (root, task) = match self.0.get_key_value(&root) {
+                Some(entry) => entry,
+                None => {
+                    missing.push((root.clone(), ExecuteError::MissingDependency(root)));
+                    match keep_going {
+                        true => continue,
+                        false => break,
+                    }
+                }
+            };
+
+            // Every task on the stack waits for its dependencies, of which `resolved` were already ordered
+            let mut stack = vec![(root, task.dependencies(), 0)];
+            seen.insert(root);
+
+            while let Some((key, dependencies, resolved)) = stack.last_mut() {
+                let key = *key;
+                let Some(dep) = dependencies.get(*resolved) else {
+                    order.push(key.clone());
+                    stack.pop();
+                    continue;
+                };
+                *resolved += 1;
+
+                if seen.contains(dep) {
+                    continue;
+                }
+
+                match self.0.get_key_value(dep) {
+                    Some((dep, task)) => {
+                        seen.insert(dep);
+                        stack.push((dep, task.dependencies(), 0));
+                    }
+                    None => {
+                        let dep = dep.clone();
+                        // The stack holds the chain of dependencies leading from the root to `key`
+                        let path = stack.iter().map(|(key, ..)| (*key).clone()).collect();
+                        let err = ExecuteError::DanglingDependency(dep, key.clone());
+                        missing.push((key.clone(), err.with_path(path)));
+                        if !keep_going {
+                            return (order, missing);
+                        }
+                    }
+                }
+            }
+        }
+
+        (order, missing)
+    }
+
+    /// Wraps an error raised while executing `key` with the path leading to it
+    /// from the first of `targets` that depends on it.
+    pub(crate) fn error_context(
+        &self,
+        targets: &[K],
+        key: &K,
+        err: ExecuteError<K>,
+    ) -> ExecuteError<K> {
+        let path = targets
+            .iter()
+            .map(|target| self.dependency_path(target, key))
+            .find(|path| !path.is_empty())
+            .unwrap_or_else(|| vec![key.clone()]);
+
+        err.with_path(path)
     }
 }
 
-impl<'tasks, O: Clone> DSK<'tasks, O> {
+impl<'tasks, O: Clone, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
     /// Executes the queried task, resolving it's dependencies and caching the result
-    /// O implements Clone, so we can cache the result
+    /// O implements Clone, so we can cache the result.
+    /// Every resolved dependency's output is inserted into `cache` before the dependent task runs.
+    /// Cached results are recomputed if a dependency changed since, e.g. after `set_input`.
+    /// Errors are wrapped in `ExecuteError::Context`, along with the path leading from `task_name` to the failing task
     pub fn execute(
         &mut self,
-        task_name: &'tasks str,
+        task_name: K,
         // ==+== ==+== ==+== //
-        cache: &mut BTreeMap<&'tasks str, O>,
-    ) -> Result<O, ExecuteError> {
-        let dependencies = {
-            let task = self.0.get(task_name);
-            task.map(|d| d.dependencies())
-                .ok_or(ExecuteError::MissingDependency(task_name.to_string()))?
+        cache: &mut BTreeMap<K, O>,
+    ) -> Result<O, ExecuteError<K>> {
+        self.execute_with(task_name, cache, &|_, _| false)
+    }
+
+    /// Resolves `task_name` like `execute`, `same` decides whether a recomputed output actually changed.
+    fn execute_with(
+        &mut self,
+        task_name: K,
+        cache: &mut BTreeMap<K, O>,
+        same: &impl Fn(&O, &O) -> bool,
+    ) -> Result<O, ExecuteError<K>> {
+        let (order, missing) = self.execution_order([task_name.clone()]);
+
+        let mut output = None;
+        for key in &order {
+            match self.execute_task(key, cache, same) {
+                Ok(result) => output = Some(result),
+                Err(err) => return Err(self.error_context(&[task_name], key, err)),
+            }
+        }
+
+        match missing {
+            Some(err) => Err(err),
+            None => Ok(output.unwrap()),
+        }
+    }
+
+    /// Executes every queried task, resolving the union of their dependencies only once.
+    /// Returns the output of each queried task, or the first error `execute` would return when executing them in turn
+    pub fn execute_many(&mut self, task_names: &[K]) -> Result<BTreeMap<K, O>, ExecuteError<K>> {
+        let (order, missing) = self.execution_order(task_names.iter().cloned());
+
+        let mut cache = BTreeMap::new();
+        let mut outputs = BTreeMap::new();
+        for key in &order {
+            let output = self
+                .execute_task(key, &mut cache, &|_, _| false)
+                .map_err(|err| self.error_context(task_names, key, err))?;
+            if task_names.contains(key) {
+                outputs.insert(key.clone(), output);
+            }
+        }
+
+        match missing {
+            Some(err) => Err(err),
+            None => Ok(outputs),
+        }
+    }
+
+    /// Brings a single task up to date, like `execute`, assuming its dependencies already are.
+    /// Callers run tasks in execution order, so a dependency without a result can only be a missing one.
+    pub(crate) fn execute_task(
+        &mut self,
+        task_name: &K,
+        cache: &mut BTreeMap<K, O>,
+        same: &impl Fn(&O, &O) -> bool,
+    ) -> Result<O, ExecuteError<K>> {
+        let dependencies = match self.0.get(task_name) {
+            Some(task) => task.dependencies().into_owned(),
+            None => return Err(ExecuteError::MissingDependency(task_name.clone())),
         };
 
+        let mut changed_at = 0;
         for dep in dependencies {
-            self.execute(dep, cache)?;
+            let output = match self.0.get(&dep).and_then(|task| task.result.clone()) {
+                Some(output) => output,
+                None => return Err(ExecuteError::MissingDependency(dep)),
+            };
+            changed_at = changed_at.max(self.0[&dep].revisions.changed_at);
+            cache.insert(dep, output);
         }
 
-        let map = &mut self.0;
-        let task = map
-            .get_mut(task_name)
-            .ok_or(ExecuteError::MissingDependency(task_name.to_string()))?;
-        task.get(cache).cloned()
+        let revision = self.1;
+        let task = self.0.get_mut(task_name).unwrap();
+        task.refresh(cache, revision, changed_at, same).cloned()
+    }
+}
+
+impl<'tasks, O: Clone + PartialEq, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
+    /// Executes the queried task like `execute`, with early cutoff:
+    /// a recomputed output equal to the previous one doesn't cause its dependents to be recomputed
+    pub fn execute_incremental(
+        &mut self,
+        task_name: K,
+        // ==+== ==+== ==+== //
+        cache: &mut BTreeMap<K, O>,
+    ) -> Result<O, ExecuteError<K>> {
+        self.execute_with(task_name, cache, &PartialEq::eq)
+    }
+}
+
+impl<'tasks, O, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
+    /// Generates a new DSK from an Iterator of keys and Tasks, validating the graph once all are inserted.
+    /// Unlike `collect`, duplicate keys, cycles and missing dependencies are returned as errors.
+    pub fn try_from_iter<I, T>(
+        iter: impl IntoIterator<Item = (I, T)>,
+    ) -> Result<Self, ExecuteError<K>>
+    where
+        I: Into<K>,
+        T: Task<O, K> + 'tasks,
+    {
+        DSKBuilder::new().add_tasks(iter).build()
     }
 }
 
-impl<'tasks, O, I, T> core::iter::FromIterator<(I, T)> for DSK<'tasks, O>
+impl<'tasks, O, K, I, T> core::iter::FromIterator<(I, T)> for DSK<'tasks, O, K>
 where
-    I: Into<&'tasks str>,
-    T: Task<O> + 'tasks,
+    K: Ord + Clone + fmt::Debug + 'tasks,
+    I: Into<K>,
+    T: Task<O, K> + 'tasks,
 {
-    /// Generates a new DSK from an Iterator of str and Tasks
+    /// Generates a new DSK from an Iterator of keys and Tasks
     fn from_iter<Iter: IntoIterator<Item = (I, T)>>(iter: Iter) -> Self {
         let mut dsk = Self::new();
 
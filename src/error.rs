@@ -1,18 +1,160 @@
-use alloc::{string::String, vec::Vec};
+use crate::ValidationReport;
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt;
 
-#[derive(Debug, thiserror_no_std::Error)]
+#[derive(Debug)]
 /// Any error encountered during task insertion or evaluation
-pub enum ExecuteError {
+pub enum ExecuteError<K = &'static str> {
     /// The specified task does not exist in the DSK
-    #[error("Key {0} is not a key in the graph")]
-    MissingDependency(String),
+    MissingDependency(K),
+    /// A task depends on a key that does not exist in the DSK. Holds the missing key, then the task declaring it
+    DanglingDependency(K, K),
     /// A NullTask is used for structural purposes. It should never be executed.
-    #[error("Attempted to execute a NULL Task")]
     NullTask,
+    /// An AsyncTask can only be executed through `DSK::execute_async`.
+    AsyncTask,
+    /// A task's output was requested as a type it does not produce
+    TypeMismatch(K, &'static str),
+    /// Only inputs, added through `DSK::add_input`, can have their value set
+    NotAnInput(K),
     /// A circular dependency chain was detected
-    #[error("A circular dependency chain: (0:?)was detected")]
-    CyclicDependency(Vec<String>),
+    CyclicDependency(Vec<K>),
     /// Tried to insert a Task into a slot that already has a Task. Which would overwrite the existing Task.c
-    #[error("A Task with the key: {0} already exists")]
-    TaskAlreadyExists(String),
+    TaskAlreadyExists(K),
+    /// Tried to remove a Task that other Tasks still depend on
+    HasDependents(K, Vec<K>),
+    /// A task failed, for a reason of its own
+    Failed(Box<dyn core::error::Error + Send + Sync>),
+    /// An error raised while executing the last key of `path`, which was reached from the first key through the
+    /// others. Each key depends on the next, and nested graphs extend the path with their own keys
+    Context {
+        /// The keys leading to the failing one, starting with the executed key
+        path: Vec<K>,
+        /// The error raised by the last key of `path`
+        error: Box<Self>,
+    },
+    /// The graph has cycles or missing dependencies, all of which are listed in the report
+    InvalidGraph(ValidationReport<K>),
+}
+
+impl<K> ExecuteError<K> {
+    /// Wraps an error raised by a task itself.
+    pub fn failed(err: impl core::error::Error + Send + Sync + 'static) -> Self {
+        ExecuteError::Failed(Box::new(err))
+    }
+
+    /// Converts every key held by this error through `f`.
+    pub fn map_keys<J: Ord>(self, f: impl Fn(K) -> J) -> ExecuteError<J> {
+        match self {
+            ExecuteError::MissingDependency(key) => ExecuteError::MissingDependency(f(key)),
+            ExecuteError::DanglingDependency(key, dependent) => {
+                ExecuteError::DanglingDependency(f(key), f(dependent))
+            }
+            ExecuteError::NullTask => ExecuteError::NullTask,
+            ExecuteError::AsyncTask => ExecuteError::AsyncTask,
+            ExecuteError::TypeMismatch(key, ty) => ExecuteError::TypeMismatch(f(key), ty),
+            ExecuteError::NotAnInput(key) => ExecuteError::NotAnInput(f(key)),
+            ExecuteError::CyclicDependency(keys) => {
+                ExecuteError::CyclicDependency(keys.into_iter().map(f).collect())
+            }
+            ExecuteError::TaskAlreadyExists(key) => ExecuteError::TaskAlreadyExists(f(key)),
+            ExecuteError::HasDependents(key, dependents) => {
+                ExecuteError::HasDependents(f(key), dependents.into_iter().map(&f).collect())
+            }
+            ExecuteError::Failed(err) => ExecuteError::Failed(err),
+            ExecuteError::Context { path, error } => ExecuteError::Context {
+                path: path.into_iter().map(&f).collect(),
+                error: Box::new(error.map_keys(f)),
+            },
+            ExecuteError::InvalidGraph(report) => ExecuteError::InvalidGraph(ValidationReport {
+                cycles: report
+                    .cycles
+                    .into_iter()
+                    .map(|cycle| cycle.into_iter().map(&f).collect())
+                    .collect(),
+                missing: report
+                    .missing
+                    .into_iter()
+                    .map(|(key, dependents)| (f(key), dependents.into_iter().map(&f).collect()))
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Wraps this error with the path leading to the key that raised it.
+    /// The path of an error that already has one is appended to `path`, so nested paths read as one.
+    pub fn with_path(self, mut path: Vec<K>) -> Self {
+        match self {
+            ExecuteError::Context { path: inner, error } => {
+                path.extend(inner);
+                ExecuteError::Context { path, error }
+            }
+            err => ExecuteError::Context {
+                path,
+                error: Box::new(err),
+            },
+        }
+    }
+
+    /// The error that caused this one, stripped of every `Context` wrapping it.
+    pub fn root_cause(&self) -> &Self {
+        match self {
+            ExecuteError::Context { error, .. } => error.root_cause(),
+            err => err,
+        }
+    }
+
+    /// The keys leading from the executed key to the failing one, if this error has any.
+    pub fn path(&self) -> Option<&[K]> {
+        match self {
+            ExecuteError::Context { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl<K: fmt::Debug> fmt::Display for ExecuteError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecuteError::MissingDependency(key) => {
+                write!(f, "Key {key:?} is not a key in the graph")
+            }
+            ExecuteError::DanglingDependency(key, dependent) => write!(
+                f,
+                "Key {key:?}, a dependency of {dependent:?}, is not a key in the graph"
+            ),
+            ExecuteError::NullTask => write!(f, "Attempted to execute a NULL Task"),
+            ExecuteError::AsyncTask => {
+                write!(f, "Attempted to synchronously execute an async Task")
+            }
+            ExecuteError::TypeMismatch(key, ty) => {
+                write!(f, "Key {key:?} does not hold a value of type {ty}")
+            }
+            ExecuteError::NotAnInput(key) => write!(f, "Key {key:?} is not an input"),
+            ExecuteError::CyclicDependency(keys) => {
+                write!(f, "A circular dependency chain: {keys:?} was detected")
+            }
+            ExecuteError::TaskAlreadyExists(key) => {
+                write!(f, "A Task with the key: {key:?} already exists")
+            }
+            ExecuteError::HasDependents(key, dependents) => write!(
+                f,
+                "The Task with the key: {key:?} is still depended upon by: {dependents:?}"
+            ),
+            ExecuteError::Failed(err) => write!(f, "Task failed: {err}"),
+            ExecuteError::Context { path, error } => write!(f, "{error} (at {path:?})"),
+            ExecuteError::InvalidGraph(report) => write!(f, "The graph is invalid: {report:?}"),
+        }
+    }
+}
+
+/// Implemented by hand, as the error wrapped in a `Context` can only be its source if its keys are `'static`
+impl<K: fmt::Debug + 'static> core::error::Error for ExecuteError<K> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ExecuteError::Failed(err) => Some(err.as_ref()),
+            ExecuteError::Context { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,304 @@
+use crate::DSK;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Write};
+
+/// Options controlling which nodes of an exported graph are highlighted.
+#[derive(Debug, Clone)]
+pub struct ExportOptions<K> {
+    /// Highlight the tasks whose result is already computed.
+    pub computed: bool,
+    /// Highlight these tasks as having errored.
+    pub errored: BTreeSet<K>,
+    /// Dim the tasks that `cull` would remove, when keeping only these keys.
+    pub culled_to: Option<Vec<K>>,
+}
+
+impl<K> Default for ExportOptions<K> {
+    fn default() -> Self {
+        Self {
+            computed: false,
+            errored: BTreeSet::new(),
+            culled_to: None,
+        }
+    }
+}
+
+/// How a node of an exported graph is drawn.
+#[derive(Default)]
+struct Highlight {
+    computed: bool,
+    errored: bool,
+    culled: bool,
+    missing: bool,
+}
+
+impl<'tasks, O, K: Ord + Clone + fmt::Display + 'tasks> DSK<'tasks, O, K> {
+    /// Renders this DSK as a Graphviz DOT document.
+    /// Edges point from a dependency to the task depending on it, in the direction data flows.
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&ExportOptions::default())
+    }
+
+    /// Renders this DSK as a Graphviz DOT document, highlighting nodes according to `options`.
+    /// Missing dependencies are drawn as dangling red nodes.
+    pub fn to_dot_with(&self, options: &ExportOptions<K>) -> String {
+        let mut dot = String::from("digraph {\n");
+
+        for (key, highlight) in self.highlights(options) {
+            let mut attributes = Vec::new();
+            let mut style = Vec::new();
+            if highlight.computed {
+                style.push("filled");
+                attributes.push("fillcolor=palegreen".to_string());
+            }
+            if highlight.errored {
+                style.push("filled");
+                attributes.push("fillcolor=salmon".to_string());
+            }
+            if highlight.culled {
+                style.push("dashed");
+                attributes.push("color=gray fontcolor=gray".to_string());
+            }
+            if highlight.missing {
+                style.push("dashed");
+                attributes.push("color=red fontcolor=red".to_string());
+            }
+
+            style.dedup();
+            if !style.is_empty() {
+                attributes.insert(0, format!("style=\"{}\"", style.join(",")));
+            }
+
+            let _ = match attributes.is_empty() {
+                true => writeln!(dot, "    {};", quote(&key)),
+                false => writeln!(dot, "    {} [{}];", quote(&key), attributes.join(" ")),
+            };
+        }
+
+        for (key, task) in self.0.iter() {
+            for dep in task.dependencies().iter() {
+                let color = match self.0.contains_key(dep) {
+                    true => "",
+                    false => " [color=red]",
+                };
+                let _ = writeln!(dot, "    {} -> {}{color};", quote(dep), quote(key));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders this DSK as a Mermaid flowchart, for embedding in Markdown.
+    /// Edges point from a dependency to the task depending on it, in the direction data flows.
+    pub fn to_mermaid(&self) -> String {
+        self.to_mermaid_with(&ExportOptions::default())
+    }
+
+    /// Renders this DSK as a Mermaid flowchart, highlighting nodes according to `options`.
+    /// Missing dependencies are drawn as dangling red nodes.
+    pub fn to_mermaid_with(&self, options: &ExportOptions<K>) -> String {
+        let mut mermaid = String::from("flowchart LR\n");
+        let highlights = self.highlights(options);
+        let ids = highlights
+            .iter()
+            .enumerate()
+            .map(|(i, (key, _))| (key, i))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut classes: [(&str, &str, Vec<usize>); 4] = [
+            ("computed", "fill:#98fb98", Vec::new()),
+            ("errored", "fill:#fa8072", Vec::new()),
+            ("culled", "stroke-dasharray:5 5,color:#808080", Vec::new()),
+            (
+                "missing",
+                "stroke:#f00,stroke-dasharray:5 5,color:#f00",
+                Vec::new(),
+            ),
+        ];
+
+        for (i, (key, highlight)) in highlights.iter().enumerate() {
+            let label = key.to_string().replace('"', "#quot;");
+            let _ = writeln!(mermaid, "    n{i}[\"{label}\"]");
+
+            let flags = [
+                highlight.computed,
+                highlight.errored,
+                highlight.culled,
+                highlight.missing,
+            ];
+            for (class, flag) in classes.iter_mut().zip(flags) {
+                if flag {
+                    class.2.push(i);
+                }
+            }
+        }
+
+        let mut edges = 0;
+        let mut dangling = Vec::new();
+        for (key, task) in self.0.iter() {
+            for dep in task.dependencies().iter() {
+                let _ = writeln!(mermaid, "    n{} --> n{}", ids[dep], ids[key]);
+                if !self.0.contains_key(dep) {
+                    dangling.push(edges.to_string());
+                }
+                edges += 1;
+            }
+        }
+
+        for (class, style, nodes) in classes.iter().filter(|class| !class.2.is_empty()) {
+            let nodes = nodes.iter().map(|i| format!("n{i}")).collect::<Vec<_>>();
+            let _ = writeln!(mermaid, "    classDef {class} {style}");
+            let _ = writeln!(mermaid, "    class {} {class}", nodes.join(","));
+        }
+
+        if !dangling.is_empty() {
+            let _ = writeln!(mermaid, "    linkStyle {} stroke:#f00", dangling.join(","));
+        }
+
+        mermaid
+    }
+
+    /// Every node of the graph, including missing dependencies, along with how it should be highlighted.
+    fn highlights(&self, options: &ExportOptions<K>) -> Vec<(K, Highlight)> {
+        let kept = options.culled_to.as_ref().map(|keys| {
+            let mut kept = BTreeSet::new();
+            let mut stack = keys.iter().collect::<Vec<_>>();
+            while let Some(key) = stack.pop() {
+                if let Some((key, task)) = self.0.get_key_value(key) {
+                    if kept.insert(key) {
+                        stack.extend(
+                            task.dependencies()
+                                .iter()
+                                .filter_map(|dep| self.0.get_key_value(dep).map(|(dep, _)| dep)),
+                        );
+                    }
+                }
+            }
+            kept
+        });
+
+        let missing = self
+            .0
+            .values()
+            .flat_map(|task| task.dependencies().into_owned())
+            .filter(|dep| !self.0.contains_key(dep))
+            .collect::<BTreeSet<_>>();
+
+        let present = self.0.iter().map(|(key, task)| {
+            let highlight = Highlight {
+                computed: options.computed && task.is_computed(),
+                errored: options.errored.contains(key),
+                culled: kept.as_ref().is_some_and(|kept| !kept.contains(key)),
+                missing: false,
+            };
+            (key.clone(), highlight)
+        });
+
+        let missing = missing.into_iter().map(|key| {
+            let highlight = Highlight {
+                missing: true,
+                ..Highlight::default()
+            };
+            (key, highlight)
+        });
+
+        present.chain(missing).collect()
+    }
+}
+
+/// Quotes a key as a DOT identifier.
+fn quote(key: &impl fmt::Display) -> String {
+    let key = key.to_string().replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{key}\"")
+}
+
+/// The version of the JSON export's schema, bumped on every incompatible change.
+#[cfg(feature = "serde")]
+pub const EXPORT_VERSION: u32 = 1;
+
+/// The structure of a DSK, for consumption by tools that don't link to Rust.
+/// Serialized through `to_json`, and read back through `from_json`.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GraphExport<K> {
+    /// The schema version, always `EXPORT_VERSION` when exported by this crate.
+    pub version: u32,
+    /// Every task of the graph, ordered by key.
+    pub tasks: Vec<TaskExport<K>>,
+}
+
+/// A single task of a [`GraphExport`].
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TaskExport<K> {
+    /// The task's key.
+    pub key: K,
+    /// The keys the task depends on, which need not be in the graph.
+    pub dependencies: Vec<K>,
+    /// Whether the task's result is already computed.
+    pub computed: bool,
+    /// Whether the task is an input, holding a result that is set rather than computed.
+    pub input: bool,
+    /// Arbitrary metadata attached on export.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[cfg(feature = "serde")]
+impl<K: serde::Serialize> GraphExport<K> {
+    /// Serializes the export to JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K: serde::de::DeserializeOwned> GraphExport<K> {
+    /// Reads an export from JSON, rejecting any schema version other than `EXPORT_VERSION`.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let export: Self = serde_json::from_str(json)?;
+        match export.version {
+            EXPORT_VERSION => Ok(export),
+            version => Err(serde::de::Error::custom(format!(
+                "unsupported export version {version}, expected {EXPORT_VERSION}"
+            ))),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'tasks, O, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
+    /// Captures the structure of this DSK: its keys, their dependencies and whether they are computed.
+    pub fn export(&self) -> GraphExport<K> {
+        self.export_with(|_| None)
+    }
+
+    /// Captures the structure of this DSK like `export`, attaching the metadata `metadata` returns to each task.
+    pub fn export_with(
+        &self,
+        metadata: impl Fn(&K) -> Option<serde_json::Value>,
+    ) -> GraphExport<K> {
+        let tasks = self
+            .0
+            .iter()
+            .map(|(key, task)| TaskExport {
+                key: key.clone(),
+                dependencies: task.dependencies().into_owned(),
+                computed: task.is_computed(),
+                input: task.is_input(),
+                metadata: metadata(key),
+            })
+            .collect();
+
+        GraphExport {
+            version: EXPORT_VERSION,
+            tasks,
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{cache::Calculation, options::fall_back, Dependencies, ExecuteError, Task, DSK};
+use alloc::{borrow::Cow, boxed::Box, collections::BTreeMap, vec::Vec};
+use core::{future::Future, pin::Pin, task::Poll};
+
+/// The boxed future returned by [`AsyncTask::execute`].
+pub type TaskFuture<'a, O, K = &'static str> =
+    Pin<Box<dyn Future<Output = Result<O, ExecuteError<K>>> + 'a>>;
+
+/// A running task's future, resolving to its result along with how many attempts it took.
+type Attempts<'a, O, K> = Pin<Box<dyn Future<Output = (Result<O, ExecuteError<K>>, u32)> + 'a>>;
+
+/// An AsyncTask is a unit of work whose execution can be awaited.
+/// It can have dependencies on other tasks, just like a [`Task`].
+pub trait AsyncTask<O, K: Clone = &'static str> {
+    /// Start executing this task, returning a future that resolves to its result.
+    /// The cache contains the results of this task's dependencies.
+    fn execute<'a>(&'a mut self, cache: &'a BTreeMap<K, O>) -> TaskFuture<'a, O, K>;
+
+    /// Lists out this task's dependencies.
+    /// These tasks' results are available later in the `cache` argument of the `execute` method.
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        Cow::Borrowed(&[])
+    }
+
+    /// Identifies the computation this task performs, just like [`Task::fingerprint`].
+    fn fingerprint(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// A boxed AsyncTask is itself an AsyncTask
+impl<'t, O, K: Clone> AsyncTask<O, K> for Box<dyn AsyncTask<O, K> + 't> {
+    fn execute<'a>(&'a mut self, cache: &'a BTreeMap<K, O>) -> TaskFuture<'a, O, K> {
+        self.as_mut().execute(cache)
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        self.as_ref().dependencies()
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        self.as_ref().fingerprint()
+    }
+}
+
+/// Adapts a synchronous [`Task`] into an [`AsyncTask`].
+/// The wrapped task runs to completion the first time its future is polled.
+pub struct Blocking<T>(pub T);
+
+impl<O, K: Clone, T: Task<O, K>> AsyncTask<O, K> for Blocking<T> {
+    fn execute<'a>(&'a mut self, cache: &'a BTreeMap<K, O>) -> TaskFuture<'a, O, K> {
+        Box::pin(async move { self.0.execute(cache) })
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        self.0.dependencies()
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        self.0.fingerprint()
+    }
+}
+
+impl<'tasks, O: Clone, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
+    /// Executes the queried task like `execute`, polling independent async tasks concurrently.
+    /// Synchronous tasks run in place as soon as their dependencies are resolved.
+    /// The returned future does not rely on any particular runtime
+    pub async fn execute_async(
+        &mut self,
+        task_name: K,
+        // ==+== ==+== ==+== //
+        cache: &mut BTreeMap<K, O>,
+    ) -> Result<O, ExecuteError<K>> {
+        let mut schedule = self.schedule(task_name.clone());
+        let mut slots = self.slots(&schedule);
+        let mut results = (0..slots.len()).map(|_| None).collect::<Vec<_>>();
+        let mut in_flight: Vec<(usize, Attempts<'_, O, K>)> = Vec::new();
+
+        core::future::poll_fn(|cx| loop {
+            let mut done = Vec::new();
+
+            while let Some(i) = schedule.next_ready() {
+                let slot = slots[i].take().unwrap();
+                if let Some(output) = schedule.cached(i, slot.result, slot.revisions) {
+                    done.push((i, (Ok(output), 0)));
+                    continue;
+                }
+
+                let inputs = schedule.inputs(i);
+                let retry = slot.retry;
+                match slot.calculation {
+                    // Waiting here would block every in-flight task, so sync tasks are retried right away too
+                    Calculation::Local(task) => {
+                        done.push((i, retry.run_now(|| task.execute(&inputs))))
+                    }
+                    Calculation::Send(task) => {
+                        done.push((i, retry.run_now(|| task.execute(&inputs))))
+                    }
+                    Calculation::Async(task) => {
+                        let task = task.as_mut();
+                        // There's no timer to wait on, so async tasks are retried right away
+                        in_flight.push((
+                            i,
+                            Box::pin(async move {
+                                let mut attempt = 1;
+                                loop {
+                                    match task.execute(&inputs).await {
+                                        Err(err) if retry.retries(attempt, &err) => attempt += 1,
+                                        output => return (output, attempt),
+                                    }
+                                }
+                            }),
+                        ));
+                    }
+                }
+                results[i] = Some((slot.result, slot.revisions, slot.fallback));
+            }
+
+            in_flight.retain_mut(|(i, future)| match future.as_mut().poll(cx) {
+                Poll::Ready(output) => {
+                    done.push((*i, output));
+                    false
+                }
+                Poll::Pending => true,
+            });
+
+            if done.is_empty() {
+                return match in_flight.is_empty() {
+                    true => Poll::Ready(()),
+                    false => Poll::Pending,
+                };
+            }
+
+            for (i, (mut output, attempts)) in done {
+                if let Some((result, revisions, fallback)) = results[i].take() {
+                    revisions.attempts = attempts;
+                    let inputs = schedule.inputs(i);
+                    output = fall_back(fallback, revisions, &inputs, output);
+                    if let Ok(output) = &output {
+                        schedule.store(i, result, revisions, output);
+                    }
+                }
+                schedule.complete(i, output);
+            }
+        })
+        .await;
+
+        // Every future borrowing a task has completed, or was never polled
+        drop(in_flight);
+        schedule.finish(cache, |key, err| self.error_context(&[task_name], key, err))
+    }
+}
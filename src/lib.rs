@@ -3,48 +3,222 @@
 
 //! A simple Task Execution and Dependency management crate, reminiscent of dask.py.
 
+mod builder;
 mod cache;
+mod compose;
 mod dsk;
 mod error;
+mod export;
+mod future;
+mod memo;
+mod options;
+#[cfg(feature = "std")]
+mod parallel;
+#[cfg(all(feature = "std", feature = "serde"))]
+mod persist;
+mod plan;
+mod report;
+mod retry;
+mod schedule;
+mod subgraph;
+mod typed;
+mod validate;
 
 mod tests;
 
+pub use builder::DSKBuilder;
 pub use cache::Cache;
+pub use compose::OnConflict;
 pub use dsk::*;
 pub use error::ExecuteError;
+pub use export::ExportOptions;
+#[cfg(feature = "serde")]
+pub use export::{GraphExport, TaskExport, EXPORT_VERSION};
+pub use future::{AsyncTask, Blocking, TaskFuture};
+pub use memo::{MemoStore, MemoryStore};
+pub use options::{Fallback, TaskOptions};
+#[cfg(all(feature = "std", feature = "serde"))]
+pub use persist::{DiskStore, PersistError, Snapshot};
+pub use plan::ExecutionPlan;
+pub use report::{ExecutionReport, Outcome};
+pub use retry::{Backoff, Exponential, RetryPolicy};
+pub use subgraph::SubGraph;
+pub use typed::{AnyOutput, Inputs, TaskKey, TypedTask};
+pub use validate::ValidationReport;
 
 extern crate alloc;
-use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+extern crate std;
+use alloc::{borrow::Cow, boxed::Box, collections::BTreeMap, vec::Vec};
 
 /// A Task is a unit of work that can be executed.
-/// It can have dependencies on other tasks.
-pub trait Task<O> {
+/// It can have dependencies on other tasks, identified by keys of type `K`.
+pub trait Task<O, K: Clone = &'static str> {
     /// Execute this task and return the result.
     /// The cache is a reference to a HashMap that contains the results of tasks' whose results are needed by this
     /// task. These tasks are called dependencies. And are listed in the `dependencies` method.
-    fn execute(&mut self, cache: &BTreeMap<&str, O>) -> Result<O, error::ExecuteError>;
+    fn execute(&mut self, cache: &BTreeMap<K, O>) -> Result<O, ExecuteError<K>>;
 
     /// Lists out this task's dependencies.
     /// These tasks' results are available later in the `cache` argument of the `execute` method.
-    fn dependencies(&self) -> &'static [&'static str] {
-        &[]
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        Cow::Borrowed(&[])
+    }
+
+    /// Identifies the computation this task performs, for persisting and memoizing its result.
+    /// Change it whenever the task's logic changes. Tasks without a fingerprint are never persisted nor memoized.
+    fn fingerprint(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// The keys a task depends on, either borrowed or computed at runtime.
+pub type Dependencies<'a, K = &'static str> = Cow<'a, [K]>;
+
+/// Wraps a task, overriding the dependencies it declares.
+/// Mostly useful for closures, which cannot declare dependencies on their own.
+pub struct WithDependencies<K, T> {
+    dependencies: Vec<K>,
+    task: T,
+}
+
+impl<K, T> WithDependencies<K, T> {
+    /// Wrap `task`, making it depend on the given keys.
+    pub fn new<D: Into<K>>(dependencies: impl IntoIterator<Item = D>, task: T) -> Self {
+        Self {
+            dependencies: dependencies.into_iter().map(Into::into).collect(),
+            task,
+        }
+    }
+}
+
+impl<O, K: Clone, T: Task<O, K>> Task<O, K> for WithDependencies<K, T> {
+    fn execute(&mut self, cache: &BTreeMap<K, O>) -> Result<O, ExecuteError<K>> {
+        self.task.execute(cache)
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        Cow::Borrowed(&self.dependencies)
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        self.task.fingerprint()
+    }
+}
+
+impl<O, K: Clone, T: AsyncTask<O, K>> AsyncTask<O, K> for WithDependencies<K, T> {
+    fn execute<'f>(&'f mut self, cache: &'f BTreeMap<K, O>) -> TaskFuture<'f, O, K> {
+        self.task.execute(cache)
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        Cow::Borrowed(&self.dependencies)
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        self.task.fingerprint()
+    }
+}
+
+/// Wraps a task, giving it a fingerprint.
+/// Mostly useful for closures, which cannot declare a fingerprint on their own.
+pub struct WithFingerprint<T> {
+    fingerprint: u64,
+    task: T,
+}
+
+impl<T> WithFingerprint<T> {
+    /// Wrap `task`, identifying it by `fingerprint`.
+    pub fn new(fingerprint: u64, task: T) -> Self {
+        Self { fingerprint, task }
+    }
+}
+
+impl<O, K: Clone, T: Task<O, K>> Task<O, K> for WithFingerprint<T> {
+    fn execute(&mut self, cache: &BTreeMap<K, O>) -> Result<O, ExecuteError<K>> {
+        self.task.execute(cache)
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        self.task.dependencies()
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        Some(self.fingerprint)
+    }
+}
+
+impl<O, K: Clone, T: AsyncTask<O, K>> AsyncTask<O, K> for WithFingerprint<T> {
+    fn execute<'f>(&'f mut self, cache: &'f BTreeMap<K, O>) -> TaskFuture<'f, O, K> {
+        self.task.execute(cache)
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        self.task.dependencies()
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        Some(self.fingerprint)
     }
 }
 
 /// Any closure is automatically a Task
-impl<T, F: Fn(&BTreeMap<&str, T>) -> T> Task<T> for F {
-    fn execute(&mut self, cache: &BTreeMap<&str, T>) -> Result<T, error::ExecuteError> {
+impl<T, K: Clone, F: Fn(&BTreeMap<K, T>) -> T> Task<T, K> for F {
+    fn execute(&mut self, cache: &BTreeMap<K, T>) -> Result<T, ExecuteError<K>> {
         Ok(self(cache))
     }
 }
 
+/// Wraps a closure that can fail with an error of its own, making it a Task.
+/// Its errors are returned as `ExecuteError::Failed`, and remain reachable through `source`.
+pub struct Fallible<F>(pub F);
+
+impl<O, K: Clone, E, F> Task<O, K> for Fallible<F>
+where
+    E: core::error::Error + Send + Sync + 'static,
+    F: Fn(&BTreeMap<K, O>) -> Result<O, E>,
+{
+    fn execute(&mut self, cache: &BTreeMap<K, O>) -> Result<O, ExecuteError<K>> {
+        (self.0)(cache).map_err(ExecuteError::failed)
+    }
+}
+
 /// A NullTask is a task that does nothing.
 /// Trying to execute it will result in an error.
 /// It is used for structural  purposes.
 pub struct NullTask;
 
-impl<T> Task<T> for NullTask {
-    fn execute(&mut self, _: &BTreeMap<&str, T>) -> Result<T, error::ExecuteError> {
+impl<T, K: Clone> Task<T, K> for NullTask {
+    fn execute(&mut self, _: &BTreeMap<K, T>) -> Result<T, ExecuteError<K>> {
         Err(ExecuteError::NullTask)
     }
 }
+
+/// A boxed Task is itself a Task
+impl<'a, O, K: Clone> Task<O, K> for Box<dyn Task<O, K> + 'a> {
+    fn execute(&mut self, cache: &BTreeMap<K, O>) -> Result<O, ExecuteError<K>> {
+        self.as_mut().execute(cache)
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        self.as_ref().dependencies()
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        self.as_ref().fingerprint()
+    }
+}
+
+impl<'a, O, K: Clone> Task<O, K> for Box<dyn Task<O, K> + Send + 'a> {
+    fn execute(&mut self, cache: &BTreeMap<K, O>) -> Result<O, ExecuteError<K>> {
+        self.as_mut().execute(cache)
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        self.as_ref().dependencies()
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        self.as_ref().fingerprint()
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{ExecuteError, DSK};
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::hash::{Hash, Hasher};
+
+/// A MemoStore keeps task outputs, keyed by a hash of the computation that produced them.
+/// It can be shared between DSKs, and persisted across runs, much like a build cache.
+/// A store is only ever a cache: anything it fails to retrieve is simply recomputed.
+pub trait MemoStore<O> {
+    /// Look up the output stored under `hash`.
+    fn get(&mut self, hash: u64) -> Option<O>;
+
+    /// Store `output` under `hash`.
+    fn insert(&mut self, hash: u64, output: &O);
+}
+
+/// A MemoStore that keeps outputs in memory.
+#[derive(Debug, Clone)]
+pub struct MemoryStore<O>(BTreeMap<u64, O>);
+
+impl<O> Default for MemoryStore<O> {
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+
+impl<O> MemoryStore<O> {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of outputs in the store.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the store holds no outputs at all.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<O: Clone> MemoStore<O> for MemoryStore<O> {
+    fn get(&mut self, hash: u64) -> Option<O> {
+        self.0.get(&hash).cloned()
+    }
+
+    fn insert(&mut self, hash: u64, output: &O) {
+        self.0.insert(hash, output.clone());
+    }
+}
+
+/// The 64-bit FNV-1a hash, which unlike `DefaultHasher` is the same across runs.
+pub(crate) struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+impl<'tasks, O: Clone + Hash, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
+    /// Executes the queried task like `execute`, looking up every task that has a fingerprint in `store` first.
+    /// A task is identified by its fingerprint together with the outputs of its dependencies,
+    /// so identical computations are reused across graphs. Outputs it does compute are added to the store.
+    /// The key of every task whose output came from the store is inserted into `hits`.
+    pub fn execute_memoized(
+        &mut self,
+        task_name: K,
+        // ==+== ==+== ==+== //
+        cache: &mut BTreeMap<K, O>,
+        store: &mut impl MemoStore<O>,
+        hits: &mut BTreeSet<K>,
+    ) -> Result<O, ExecuteError<K>> {
+        let (order, missing) = self.execution_order([task_name.clone()]);
+
+        let mut output = None;
+        for key in order {
+            match self.memoize_task(key.clone(), cache, store, hits) {
+                Ok(result) => output = Some(result),
+                Err(err) => return Err(self.error_context(&[task_name], &key, err)),
+            }
+        }
+
+        match missing {
+            Some(err) => Err(err),
+            None => Ok(output.unwrap()),
+        }
+    }
+
+    /// Brings a single task up to date through `store`, once its dependencies are.
+    fn memoize_task(
+        &mut self,
+        task_name: K,
+        cache: &mut BTreeMap<K, O>,
+        store: &mut impl MemoStore<O>,
+        hits: &mut BTreeSet<K>,
+    ) -> Result<O, ExecuteError<K>> {
+        let dependencies = self.0[&task_name].dependencies().into_owned();
+
+        let mut changed_at = 0;
+        let mut hasher = Fnv::default();
+        for dep in dependencies {
+            // Dependencies precede their dependents in the execution order, so they're already computed
+            let output = self.0[&dep].result.clone().unwrap();
+            changed_at = changed_at.max(self.0[&dep].revisions.changed_at);
+            output.hash(&mut hasher);
+            cache.insert(dep, output);
+        }
+
+        let revision = self.1;
+        let task = self.0.get_mut(&task_name).unwrap();
+
+        let memo = match task.is_fresh(changed_at) {
+            true => None,
+            false => task.fingerprint().map(|fingerprint| {
+                fingerprint.hash(&mut hasher);
+                hasher.finish()
+            }),
+        };
+
+        let hit = match memo.and_then(|memo| store.get(memo)) {
+            Some(output) => {
+                task.set_input(output, revision);
+                hits.insert(task_name);
+                true
+            }
+            None => false,
+        };
+
+        let output = task
+            .refresh(cache, revision, changed_at, |_, _| false)?
+            .clone();
+        // A fallback's output isn't what the task computes, and mustn't be reused as such
+        if let Some(memo) = memo.filter(|_| !hit && !task.revisions.fallback) {
+            store.insert(memo, &output);
+        }
+
+        Ok(output)
+    }
+}
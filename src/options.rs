@@ -0,0 +1,74 @@
+use crate::{cache::Revisions, Cache, ExecuteError, RetryPolicy, Task};
+use alloc::{boxed::Box, collections::BTreeMap};
+
+/// Per-task configuration, set when the task is added through `DSK::add_task_with`.
+pub struct TaskOptions<'tasks, O, K: Clone = &'static str> {
+    /// How the task is retried when it fails
+    pub retry: RetryPolicy,
+    /// What to use instead of the task's output, once it failed for good
+    pub fallback: Option<Fallback<'tasks, O, K>>,
+}
+
+impl<'tasks, O, K: Clone> Default for TaskOptions<'tasks, O, K> {
+    fn default() -> Self {
+        Self {
+            retry: RetryPolicy::default(),
+            fallback: None,
+        }
+    }
+}
+
+/// Stands in for a failed task, so its dependents can proceed.
+/// A result computed by the fallback is flagged in `ExecutionReport::fallbacks`, and only stands in for the task's
+/// until the next execution, which tries the task itself again.
+pub struct Fallback<'tasks, O, K: Clone = &'static str>(Box<Cache<'tasks, O, K>>);
+
+impl<'tasks, O, K: Clone + 'tasks> Fallback<'tasks, O, K> {
+    /// Fall back on a constant value.
+    pub fn value(value: O) -> Self {
+        Self(Box::new(Cache::from_result(value)))
+    }
+
+    /// Fall back on another task, which is given the outputs of the failed task's dependencies.
+    /// Its own dependencies are ignored.
+    pub fn task(task: impl Task<O, K> + 'tasks) -> Self {
+        Self(Box::new(Cache::from(task)))
+    }
+
+    /// The Cache computing the fallback's output.
+    pub(crate) fn cache_mut(&mut self) -> &mut Cache<'tasks, O, K> {
+        &mut self.0
+    }
+
+    /// Decomposes this Fallback into its Cache.
+    pub(crate) fn into_cache(self) -> Cache<'tasks, O, K> {
+        *self.0
+    }
+
+    /// Reassembles a Fallback from its Cache.
+    pub(crate) fn from_cache(cache: Cache<'tasks, O, K>) -> Self {
+        Self(Box::new(cache))
+    }
+}
+
+/// Replaces a task's failed `output` by its fallback's, recording in `revisions` whether it did.
+/// If the fallback fails as well, the task's own error is returned.
+pub(crate) fn fall_back<'tasks, O: Clone, K: Clone + 'tasks>(
+    fallback: &mut Option<Fallback<'tasks, O, K>>,
+    revisions: &mut Revisions,
+    inputs: &BTreeMap<K, O>,
+    output: Result<O, ExecuteError<K>>,
+) -> Result<O, ExecuteError<K>> {
+    revisions.fallback = false;
+    let Some(fallback) = fallback.as_mut().filter(|_| output.is_err()) else {
+        return output;
+    };
+
+    match fallback.cache_mut().compute(inputs) {
+        Ok(output) => {
+            revisions.fallback = true;
+            Ok(output)
+        }
+        Err(_) => output,
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{cache::Calculation, options::fall_back, ExecuteError, RetryPolicy, Task, DSK};
+use alloc::{collections::BTreeMap, vec::Vec};
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Mutex},
+    thread,
+};
+
+type Job<'a, 'tasks, O, K> = (
+    usize,
+    &'a mut (dyn Task<O, K> + Send + 'tasks),
+    &'a RetryPolicy,
+    BTreeMap<K, O>,
+);
+
+impl<'tasks, O: Clone + Send, K: Ord + Clone + Send + 'tasks> DSK<'tasks, O, K> {
+    /// Executes the queried task like `execute`, running independent tasks on `workers` threads.
+    /// Only tasks added through `add_send_task` are moved to worker threads, the rest run on the calling thread.
+    /// Returns the same result, or the same error, as the sequential `execute`.
+    /// A task panicking on a worker thread makes the calling thread panic, just like it would sequentially
+    pub fn execute_parallel(
+        &mut self,
+        task_name: K,
+        workers: usize,
+        // ==+== ==+== ==+== //
+        cache: &mut BTreeMap<K, O>,
+    ) -> Result<O, ExecuteError<K>> {
+        let mut schedule = self.schedule(task_name.clone());
+        let mut slots = self.slots(&schedule);
+        let mut results = (0..slots.len()).map(|_| None).collect::<Vec<_>>();
+
+        let (job_tx, job_rx) = mpsc::channel::<Job<'_, 'tasks, O, K>>();
+        let (result_tx, result_rx) = mpsc::channel();
+        let job_rx = Mutex::new(job_rx);
+
+        thread::scope(|scope| {
+            for _ in 0..workers.max(1) {
+                let (job_rx, result_tx) = (&job_rx, result_tx.clone());
+                scope.spawn(move || loop {
+                    let job = job_rx.lock().unwrap().recv();
+                    match job {
+                        Ok((i, task, retry, inputs)) => {
+                            // A panic is sent back rather than unwinding the worker,
+                            // which would leave the calling thread waiting for its result forever
+                            let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                                retry.run(|| task.execute(&inputs))
+                            }));
+                            let _ = result_tx.send((i, outcome));
+                        }
+                        Err(_) => break,
+                    }
+                });
+            }
+            drop(result_tx);
+
+            let mut in_flight = 0;
+            let mut done = Vec::new();
+
+            loop {
+                let mut local = Vec::new();
+
+                while let Some(i) = schedule.next_ready() {
+                    let slot = slots[i].take().unwrap();
+                    if let Some(output) = schedule.cached(i, slot.result, slot.revisions) {
+                        done.push((i, Ok(output), 0));
+                        continue;
+                    }
+
+                    let retry = slot.retry;
+                    match slot.calculation {
+                        Calculation::Send(task) => {
+                            let job = (i, task.as_mut(), retry, schedule.inputs(i));
+                            job_tx.send(job).unwrap();
+                            in_flight += 1;
+                        }
+                        Calculation::Local(task) => {
+                            local.push((i, task, retry, schedule.inputs(i)))
+                        }
+                        Calculation::Async(_) => done.push((i, Err(ExecuteError::AsyncTask), 0)),
+                    }
+                    results[i] = Some((slot.result, slot.revisions, slot.fallback));
+                }
+
+                for (i, task, retry, inputs) in local {
+                    let (output, attempts) = retry.run(|| task.execute(&inputs));
+                    done.push((i, output, attempts));
+                }
+
+                if done.is_empty() {
+                    if in_flight == 0 {
+                        break;
+                    }
+
+                    let (i, outcome) = result_rx.recv().expect("workers outlive their jobs");
+                    match outcome {
+                        Ok((output, attempts)) => done.push((i, output, attempts)),
+                        // Unwinding drops `job_tx`, so the idle workers stop before the scope rethrows
+                        Err(payload) => panic::resume_unwind(payload),
+                    }
+                    in_flight -= 1;
+                }
+
+                for (i, mut output, attempts) in done.drain(..) {
+                    if let Some((result, revisions, fallback)) = results[i].take() {
+                        revisions.attempts = attempts;
+                        let inputs = schedule.inputs(i);
+                        output = fall_back(fallback, revisions, &inputs, output);
+                        if let Ok(output) = &output {
+                            schedule.store(i, result, revisions, output);
+                        }
+                    }
+                    schedule.complete(i, output);
+                }
+            }
+
+            drop(job_tx);
+        });
+
+        // The workers are gone, and no job borrowing a task is left
+        drop(job_rx);
+        schedule.finish(cache, |key, err| self.error_context(&[task_name], key, err))
+    }
+}
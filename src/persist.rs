@@ -0,0 +1,204 @@
+use crate::{memo::Fnv, MemoStore, DSK};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
+use core::hash::Hasher;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The version of the on-disk snapshot format
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror_no_std::Error)]
+/// Any error encountered while saving or loading a Snapshot
+pub enum PersistError {
+    /// Reading or writing a snapshot failed
+    #[error("Snapshot I/O failed: {0}")]
+    Io(#[from] io::Error),
+    /// A key or result could not be converted to or from JSON
+    #[error("Snapshot (de)serialization failed: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The snapshot was written in a format this version cannot read
+    #[error("Unsupported snapshot format version: {0}")]
+    UnsupportedVersion(u32),
+}
+
+/// A single persisted result, along with the key and fingerprint of the task that computed it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    key: Value,
+    fingerprint: u64,
+    result: Value,
+}
+
+/// The computed results of a DSK, in a form that outlives the process.
+/// Created by `DSK::snapshot` and rehydrated by `DSK::restore`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    version: u32,
+    entries: Vec<Entry>,
+}
+
+impl Default for Snapshot {
+    fn default() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl Snapshot {
+    /// The number of results held by this snapshot.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether this snapshot holds no results at all.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes the snapshot to a single file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PersistError> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Reads a snapshot written by `save`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PersistError> {
+        let snapshot: Self = serde_json::from_slice(&fs::read(path)?)?;
+        match snapshot.version {
+            FORMAT_VERSION => Ok(snapshot),
+            version => Err(PersistError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Writes the snapshot to a directory, one file per result.
+    /// Results already in the directory are kept, unless overwritten by this snapshot's result for the same key.
+    pub fn save_dir(&self, dir: impl AsRef<Path>) -> Result<(), PersistError> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        for entry in &self.entries {
+            let single = Snapshot {
+                version: FORMAT_VERSION,
+                entries: alloc::vec![entry.clone()],
+            };
+            single.save(dir.join(file_name(&entry.key)?))?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads every result in a directory written by `save_dir`.
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Self, PersistError> {
+        let mut snapshot = Snapshot::default();
+        for file in fs::read_dir(dir)? {
+            let path = file?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                snapshot.entries.extend(Snapshot::load(path)?.entries);
+            }
+        }
+
+        Ok(snapshot)
+    }
+}
+
+/// Names the file holding a key's result after a hash of the key, as keys need not be valid file names.
+fn file_name(key: &Value) -> Result<String, PersistError> {
+    let mut hasher = Fnv::default();
+    hasher.write(&serde_json::to_vec(key)?);
+    Ok(alloc::format!("{:016x}.json", hasher.finish()))
+}
+
+/// A MemoStore that keeps outputs on disk, one file per output, so they survive across runs.
+/// Outputs that cannot be read back are treated as missing, and failing to write one only loses it.
+#[derive(Debug, Clone)]
+pub struct DiskStore(PathBuf);
+
+impl DiskStore {
+    /// Create a store backed by the directory at `dir`, creating it if needed.
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, PersistError> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self(dir.as_ref().to_path_buf()))
+    }
+
+    fn path(&self, hash: u64) -> PathBuf {
+        self.0.join(alloc::format!("{hash:016x}.json"))
+    }
+}
+
+impl<O: Serialize + DeserializeOwned> MemoStore<O> for DiskStore {
+    fn get(&mut self, hash: u64) -> Option<O> {
+        let bytes = fs::read(self.path(hash)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    fn insert(&mut self, hash: u64, output: &O) {
+        if let Ok(bytes) = serde_json::to_vec(output) {
+            let _ = fs::write(self.path(hash), bytes);
+        }
+    }
+}
+
+impl<'tasks, O: Serialize, K: Ord + Clone + Serialize + 'tasks> DSK<'tasks, O, K> {
+    /// Captures the computed result of every task that has a fingerprint.
+    /// Inputs, tasks without a fingerprint and tasks whose result came from their fallback are left out.
+    pub fn snapshot(&self) -> Result<Snapshot, PersistError> {
+        let mut snapshot = Snapshot::default();
+        for (key, task) in self.0.iter() {
+            let (Some(fingerprint), Some(result)) = (task.fingerprint(), &task.result) else {
+                continue;
+            };
+            if task.revisions.fallback {
+                continue;
+            }
+
+            snapshot.entries.push(Entry {
+                key: serde_json::to_value(key)?,
+                fingerprint,
+                result: serde_json::to_value(result)?,
+            });
+        }
+
+        Ok(snapshot)
+    }
+}
+
+impl<'tasks, O: DeserializeOwned, K: Ord + Clone + Serialize + 'tasks> DSK<'tasks, O, K> {
+    /// Rehydrates the results held by `snapshot`, returning the keys of every task it restored.
+    /// A result is only restored into a task that has not been computed yet, and whose fingerprint matches the
+    /// one it was saved with. Any other task is left as is, and will be computed on its next execution.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<BTreeSet<K>, PersistError> {
+        let entries = snapshot
+            .entries
+            .iter()
+            .map(|entry| Ok((serde_json::to_string(&entry.key)?, entry)))
+            .collect::<Result<BTreeMap<_, _>, PersistError>>()?;
+
+        let mut restored = BTreeSet::new();
+        for (key, task) in self.0.iter_mut() {
+            if task.is_computed() || task.is_input() {
+                continue;
+            }
+
+            let Some(entry) = entries.get(&serde_json::to_string(key)?) else {
+                continue;
+            };
+
+            if task.fingerprint() == Some(entry.fingerprint) {
+                task.set_input(O::deserialize(&entry.result)?, self.1);
+                restored.insert(key.clone());
+            }
+        }
+
+        Ok(restored)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{ExecuteError, DSK};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use core::fmt;
+
+/// What executing a set of targets would do, as computed by `DSK::plan`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionPlan<K> {
+    /// The tasks the plan was made for.
+    pub targets: Vec<K>,
+    /// Every task needed by the targets, dependencies first, in the order `execute` would visit them.
+    pub order: Vec<K>,
+    /// The tasks in `order` whose cached result is up to date, and won't be recomputed.
+    pub cached: BTreeSet<K>,
+    /// The tasks that will be computed, grouped into levels.
+    /// A task only depends on tasks of earlier levels, or cached ones, so each level could run in parallel.
+    pub levels: Vec<Vec<K>>,
+}
+
+impl<K: Ord> ExecutionPlan<K> {
+    /// Whether `key` will be computed when running this plan.
+    pub fn computes(&self, key: &K) -> bool {
+        self.levels.iter().any(|level| level.contains(key))
+    }
+}
+
+impl<K: fmt::Debug> fmt::Display for ExecutionPlan<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "targets: {:?}", self.targets)?;
+        writeln!(f, "cached: {:?}", self.cached)?;
+        for (i, level) in self.levels.iter().enumerate() {
+            writeln!(f, "level {i}: {level:?}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'tasks, O, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
+    /// Plans the execution of `targets`, without running anything.
+    /// Fails if any task needed by the targets is missing from the graph.
+    pub fn plan(&self, targets: &[K]) -> Result<ExecutionPlan<K>, ExecuteError<K>> {
+        let (order, missing) = self.execution_order(targets.iter().cloned());
+        if let Some(err) = missing {
+            return Err(err);
+        }
+
+        // Mirrors `execute`: a task runs if a dependency changed after it was verified,
+        // and every task that runs counts as changed at the current revision
+        let mut levels: Vec<Vec<K>> = Vec::new();
+        let mut level = BTreeMap::new();
+        let mut cached = BTreeSet::new();
+        for key in &order {
+            let task = &self.0[key];
+            let mut changed_at = 0;
+            let mut next = 0;
+            for dep in task.dependencies().iter() {
+                match level.get(dep) {
+                    Some(dep_level) => {
+                        changed_at = self.1;
+                        next = next.max(dep_level + 1);
+                    }
+                    None => changed_at = changed_at.max(self.0[dep].revisions.changed_at),
+                }
+            }
+
+            if task.is_fresh(changed_at) {
+                cached.insert(key.clone());
+                continue;
+            }
+
+            level.insert(key.clone(), next);
+            match levels.get_mut(next) {
+                Some(tasks) => tasks.push(key.clone()),
+                None => levels.push(Vec::from([key.clone()])),
+            }
+        }
+
+        Ok(ExecutionPlan {
+            targets: targets.to_vec(),
+            order,
+            cached,
+            levels,
+        })
+    }
+}
+
+impl<'tasks, O: Clone, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
+    /// Executes a plan made by `plan`, task by task, returning the output of each of its targets.
+    /// Every task's output is inserted into `cache`, like `execute` does for dependencies.
+    /// Inputs may be set between planning and running, but tasks shouldn't be added or removed.
+    pub fn run_plan(
+        &mut self,
+        plan: &ExecutionPlan<K>,
+        // ==+== ==+== ==+== //
+        cache: &mut BTreeMap<K, O>,
+    ) -> Result<BTreeMap<K, O>, ExecuteError<K>> {
+        for key in &plan.order {
+            let output = self
+                .execute_task(key, cache, &|_, _| false)
+                .map_err(|err| self.error_context(&plan.targets, key, err))?;
+            cache.insert(key.clone(), output);
+        }
+
+        Ok(plan
+            .targets
+            .iter()
+            .map(|target| (target.clone(), cache[target].clone()))
+            .collect())
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{ExecuteError, DSK};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+
+/// What became of a single task during `DSK::execute_keep_going`.
+#[derive(Debug)]
+pub enum Outcome<O, K> {
+    /// The task's output, computed or cached, or its fallback's
+    Ok(O),
+    /// The task failed, with the path leading to it from the first target needing it
+    Err(ExecuteError<K>),
+    /// The task never ran, because the task under this key failed.
+    /// It may be an indirect dependency, the one that failed rather than the one that was skipped in turn
+    Skipped(K),
+}
+
+impl<O, K> Outcome<O, K> {
+    /// The task's output, if it succeeded.
+    pub fn ok(&self) -> Option<&O> {
+        match self {
+            Outcome::Ok(output) => Some(output),
+            _ => None,
+        }
+    }
+
+    /// Whether the task succeeded.
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Outcome::Ok(_))
+    }
+}
+
+/// The outcome of every task needed by a set of targets, as returned by `DSK::execute_keep_going`.
+#[derive(Debug)]
+pub struct ExecutionReport<O, K> {
+    /// The tasks the report was made for.
+    pub targets: Vec<K>,
+    /// The outcome of every task needed by the targets, including missing targets.
+    pub outcomes: BTreeMap<K, Outcome<O, K>>,
+    /// How many times each task that ran was attempted, more than once only if it was retried.
+    /// Tasks whose cached result was reused, or that were skipped, didn't run.
+    pub attempts: BTreeMap<K, u32>,
+    /// The tasks whose output came from their fallback, because the task itself failed.
+    pub fallbacks: BTreeSet<K>,
+}
+
+impl<O, K: Ord> ExecutionReport<O, K> {
+    /// Whether every task succeeded.
+    pub fn is_success(&self) -> bool {
+        self.outcomes.values().all(Outcome::is_ok)
+    }
+
+    /// The output of `key`, if it succeeded.
+    pub fn output(&self, key: &K) -> Option<&O> {
+        self.outcomes.get(key).and_then(Outcome::ok)
+    }
+
+    /// Every task that failed, along with its error.
+    pub fn errors(&self) -> impl Iterator<Item = (&K, &ExecuteError<K>)> {
+        self.outcomes
+            .iter()
+            .filter_map(|(key, outcome)| match outcome {
+                Outcome::Err(err) => Some((key, err)),
+                _ => None,
+            })
+    }
+}
+
+impl<'tasks, O: Clone, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
+    /// Executes every queried task like `execute_many`, without stopping at the first error.
+    /// Every task whose dependencies all succeeded runs, and the others are skipped.
+    /// Successful results stay cached in the DSK, just like with `execute`
+    pub fn execute_keep_going(&mut self, task_names: &[K]) -> ExecutionReport<O, K> {
+        let (order, missing) = self.ordered(task_names.iter().cloned(), true);
+
+        let mut outcomes = missing
+            .into_iter()
+            .map(|(key, err)| (key, Outcome::Err(err)))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut cache = BTreeMap::new();
+        let mut attempts = BTreeMap::new();
+        let mut fallbacks = BTreeSet::new();
+        for key in order {
+            if outcomes.contains_key(&key) {
+                continue;
+            }
+
+            let cause =
+                self.0[&key]
+                    .dependencies()
+                    .iter()
+                    .find_map(|dep| match outcomes.get(dep) {
+                        Some(Outcome::Err(_)) => Some(dep.clone()),
+                        Some(Outcome::Skipped(cause)) => Some(cause.clone()),
+                        _ => None,
+                    });
+
+            let outcome = match cause {
+                Some(cause) => Outcome::Skipped(cause),
+                None => {
+                    let output = self.execute_task(&key, &mut cache, &|_, _| false);
+                    let revisions = self.0[&key].revisions;
+                    if revisions.attempts > 0 {
+                        attempts.insert(key.clone(), revisions.attempts);
+                    }
+                    if output.is_ok() && revisions.fallback {
+                        fallbacks.insert(key.clone());
+                    }
+
+                    match output {
+                        Ok(output) => Outcome::Ok(output),
+                        Err(err) => Outcome::Err(self.error_context(task_names, &key, err)),
+                    }
+                }
+            };
+            outcomes.insert(key, outcome);
+        }
+
+        ExecutionReport {
+            targets: task_names.to_vec(),
+            outcomes,
+            attempts,
+            fallbacks,
+        }
+    }
+}
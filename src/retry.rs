@@ -0,0 +1,145 @@
+use crate::ExecuteError;
+use alloc::boxed::Box;
+use core::time::Duration;
+
+/// How long to wait before retrying a task.
+/// Only the `std` feature can actually wait, without it tasks are retried right away.
+/// `DSK::execute_async` has no timer to wait on either, and retries every task right away.
+pub trait Backoff: Send + Sync {
+    /// The delay before the attempt following `attempt`, counting from 1.
+    fn delay(&self, attempt: u32) -> Duration;
+}
+
+/// Waits the same amount of time before every retry
+impl Backoff for Duration {
+    fn delay(&self, _: u32) -> Duration {
+        *self
+    }
+}
+
+/// Computes the delay from the attempt that just failed
+impl<F: Fn(u32) -> Duration + Send + Sync> Backoff for F {
+    fn delay(&self, attempt: u32) -> Duration {
+        self(attempt)
+    }
+}
+
+/// Doubles the delay after every attempt, starting from `initial` and never exceeding `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exponential {
+    /// The delay before the first retry
+    pub initial: Duration,
+    /// The longest delay between two attempts
+    pub max: Duration,
+}
+
+impl Backoff for Exponential {
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+}
+
+/// When, and how often, a failing task is run again.
+/// Only errors a task raised itself, `ExecuteError::Failed`, are retried:
+/// any other error comes from the graph rather than the task, and would be raised again.
+pub struct RetryPolicy {
+    /// How many times the task runs at most, including the first attempt
+    pub max_attempts: u32,
+    /// Whether the error a task failed with is worth another attempt
+    pub retryable: fn(&(dyn core::error::Error + Send + Sync + 'static)) -> bool,
+    /// How long to wait between attempts
+    pub backoff: Box<dyn Backoff>,
+}
+
+impl Default for RetryPolicy {
+    /// Runs the task once, never retrying it
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            retryable: |_| true,
+            backoff: Box::new(Duration::ZERO),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Runs a task up to `max_attempts` times, retrying any error it raises, without waiting in between.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// Only retry the errors `retryable` accepts.
+    pub fn retry_if(
+        mut self,
+        retryable: fn(&(dyn core::error::Error + Send + Sync + 'static)) -> bool,
+    ) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Wait between attempts according to `backoff`.
+    pub fn backoff(mut self, backoff: impl Backoff + 'static) -> Self {
+        self.backoff = Box::new(backoff);
+        self
+    }
+
+    /// Whether a task failing with `err` on its `attempt`th attempt should be run again.
+    /// Errors wrapped in a context, e.g. by a SubGraph, are judged by their root cause.
+    pub(crate) fn retries<K>(&self, attempt: u32, err: &ExecuteError<K>) -> bool {
+        match err.root_cause() {
+            ExecuteError::Failed(source) => {
+                attempt < self.max_attempts && (self.retryable)(source.as_ref())
+            }
+            _ => false,
+        }
+    }
+
+    /// Waits before the attempt following `attempt`.
+    pub(crate) fn wait(&self, attempt: u32) {
+        #[cfg(feature = "std")]
+        std::thread::sleep(self.backoff.delay(attempt));
+        #[cfg(not(feature = "std"))]
+        let _ = attempt;
+    }
+
+    /// Runs `execute` until it succeeds, or fails in a way that shouldn't be retried.
+    /// Returns its last result, along with how many times it ran.
+    pub(crate) fn run<O, K>(
+        &self,
+        execute: impl FnMut() -> Result<O, ExecuteError<K>>,
+    ) -> (Result<O, ExecuteError<K>>, u32) {
+        self.run_with(execute, |attempt| self.wait(attempt))
+    }
+
+    /// Runs `execute` like `run`, without ever waiting between attempts.
+    /// For callers that mustn't block, like a future being polled.
+    pub(crate) fn run_now<O, K>(
+        &self,
+        execute: impl FnMut() -> Result<O, ExecuteError<K>>,
+    ) -> (Result<O, ExecuteError<K>>, u32) {
+        self.run_with(execute, |_| {})
+    }
+
+    fn run_with<O, K>(
+        &self,
+        mut execute: impl FnMut() -> Result<O, ExecuteError<K>>,
+        wait: impl Fn(u32),
+    ) -> (Result<O, ExecuteError<K>>, u32) {
+        let mut attempt = 1;
+        loop {
+            match execute() {
+                Err(err) if self.retries(attempt, &err) => {
+                    wait(attempt);
+                    attempt += 1;
+                }
+                output => return (output, attempt),
+            }
+        }
+    }
+}
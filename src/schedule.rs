@@ -0,0 +1,204 @@
+use crate::{
+    cache::{Calculation, Revisions},
+    ExecuteError, Fallback, RetryPolicy, DSK,
+};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+
+/// A split-borrowed Cache.
+pub(crate) struct Slot<'a, 'tasks, O, K: Clone> {
+    pub(crate) result: &'a mut Option<O>,
+    pub(crate) calculation: &'a mut Calculation<'tasks, O, K>,
+    pub(crate) revisions: &'a mut Revisions,
+    pub(crate) retry: &'a RetryPolicy,
+    pub(crate) fallback: &'a mut Option<Fallback<'tasks, O, K>>,
+}
+
+/// Bookkeeping shared by the executors that run independent tasks out of order.
+/// Errors are reported exactly as the sequential `DSK::execute` would report them.
+pub(crate) struct Schedule<O, K> {
+    /// Every task needed by the root, in the order `execute` would run them
+    pub(crate) order: Vec<K>,
+    dependencies: Vec<BTreeSet<usize>>,
+    dependents: Vec<Vec<usize>>,
+    pending: Vec<usize>,
+    ready: BTreeSet<usize>,
+    outputs: Vec<Option<O>>,
+    revision: u64,
+    /// The revision at which each task's output last changed
+    changed: Vec<u64>,
+    failure: Option<(usize, ExecuteError<K>)>,
+}
+
+impl<'tasks, O, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
+    /// Prepares a Schedule for resolving `root`.
+    pub(crate) fn schedule(&self, root: K) -> Schedule<O, K> {
+        let (order, missing) = self.execution_order([root]);
+        let len = order.len();
+
+        let index = order
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key, i))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut dependencies = Vec::with_capacity(len);
+        let mut dependents = (0..len).map(|_| Vec::new()).collect::<Vec<_>>();
+        for (i, key) in order.iter().enumerate() {
+            let deps = self.0[key]
+                .dependencies()
+                .iter()
+                .filter_map(|dep| index.get(dep).copied())
+                .collect::<BTreeSet<_>>();
+
+            for dep in &deps {
+                dependents[*dep].push(i);
+            }
+            dependencies.push(deps);
+        }
+
+        let pending = dependencies.iter().map(BTreeSet::len).collect::<Vec<_>>();
+        let ready = (0..len).filter(|i| pending[*i] == 0).collect();
+
+        Schedule {
+            order,
+            dependencies,
+            dependents,
+            pending,
+            ready,
+            outputs: (0..len).map(|_| None).collect(),
+            revision: self.1,
+            changed: alloc::vec![0; len],
+            // A missing dependency is hit after every task preceding it in the sequential order
+            failure: missing.map(|err| (len, err)),
+        }
+    }
+
+    /// Split-borrows the Caches of every task in `schedule`, indexed like `schedule.order`.
+    pub(crate) fn slots<'a>(
+        &'a mut self,
+        schedule: &Schedule<O, K>,
+    ) -> Vec<Option<Slot<'a, 'tasks, O, K>>> {
+        let mut slots = (0..schedule.order.len()).map(|_| None).collect::<Vec<_>>();
+        let mut keys = schedule.order.iter().enumerate().collect::<Vec<_>>();
+        keys.sort_by_key(|(_, key)| *key);
+
+        let mut keys = keys.into_iter().peekable();
+        for (key, task) in self.0.iter_mut() {
+            if let Some((i, _)) = keys.next_if(|(_, k)| *k == key) {
+                slots[i] = Some(task.split_mut());
+            }
+        }
+
+        slots
+    }
+}
+
+impl<O: Clone, K: Ord + Clone> Schedule<O, K> {
+    /// The next task whose dependencies are all resolved.
+    /// Tasks the sequential executor would never reach, because of an earlier failure, are skipped.
+    pub(crate) fn next_ready(&mut self) -> Option<usize> {
+        while let Some(i) = self.ready.pop_first() {
+            if self.failure.as_ref().is_none_or(|(f, _)| i < *f) {
+                return Some(i);
+            }
+        }
+
+        None
+    }
+
+    /// A ready task's cached result, if none of its dependencies changed since it was last verified.
+    pub(crate) fn cached(
+        &mut self,
+        i: usize,
+        result: &Option<O>,
+        revisions: &mut Revisions,
+    ) -> Option<O> {
+        let changed_at = self.dependencies[i]
+            .iter()
+            .map(|dep| self.changed[*dep])
+            .max();
+        let fresh = revisions.input
+            || (!revisions.fallback && revisions.verified_at >= changed_at.unwrap_or(0));
+
+        let output = result.as_ref().filter(|_| fresh)?;
+        revisions.verified_at = self.revision;
+        revisions.attempts = 0;
+        self.changed[i] = revisions.changed_at;
+        Some(output.clone())
+    }
+
+    /// Caches a task's freshly computed output.
+    pub(crate) fn store(
+        &mut self,
+        i: usize,
+        result: &mut Option<O>,
+        revisions: &mut Revisions,
+        output: &O,
+    ) {
+        *result = Some(output.clone());
+        revisions.changed_at = self.revision;
+        if !revisions.fallback {
+            revisions.verified_at = self.revision;
+        }
+        self.changed[i] = self.revision;
+    }
+
+    /// The outputs of a ready task's dependencies.
+    pub(crate) fn inputs(&self, i: usize) -> BTreeMap<K, O> {
+        self.dependencies[i]
+            .iter()
+            .map(|dep| {
+                (
+                    self.order[*dep].clone(),
+                    self.outputs[*dep].clone().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    /// Records the outcome of a task, readying any dependents it unblocks.
+    pub(crate) fn complete(&mut self, i: usize, output: Result<O, ExecuteError<K>>) {
+        match output {
+            Ok(output) => {
+                self.outputs[i] = Some(output);
+                for dependent in &self.dependents[i] {
+                    self.pending[*dependent] -= 1;
+                    if self.pending[*dependent] == 0 {
+                        self.ready.insert(*dependent);
+                    }
+                }
+            }
+            Err(err) => {
+                if self.failure.as_ref().is_none_or(|(f, _)| i < *f) {
+                    self.failure = Some((i, err));
+                }
+            }
+        }
+    }
+
+    /// Returns the root's output, inserting every dependency's output into `cache`.
+    /// A task's error is passed to `context` along with the task's key, a missing key's is returned as is.
+    pub(crate) fn finish(
+        self,
+        cache: &mut BTreeMap<K, O>,
+        context: impl FnOnce(&K, ExecuteError<K>) -> ExecuteError<K>,
+    ) -> Result<O, ExecuteError<K>> {
+        if let Some((i, err)) = self.failure {
+            return Err(match self.order.get(i) {
+                Some(key) => context(key, err),
+                None => err,
+            });
+        }
+
+        let mut outputs = self.outputs;
+        let output = outputs.pop().flatten().unwrap();
+        for (key, dep) in self.order.into_iter().zip(outputs) {
+            cache.insert(key, dep.unwrap());
+        }
+
+        Ok(output)
+    }
+}
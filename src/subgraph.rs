@@ -0,0 +1,60 @@
+use crate::{Dependencies, ExecuteError, Task, DSK};
+use alloc::{borrow::Cow, collections::BTreeMap, vec::Vec};
+
+/// A whole DSK, packaged as a single Task of another DSK.
+/// Executing it executes the inner DSK's `output` task, after injecting the outputs of its dependencies as inputs.
+/// Errors from the inner DSK are wrapped in `ExecuteError::Context`, whose path leads from `output` to the failing key.
+pub struct SubGraph<'tasks, O, K: Clone = &'static str> {
+    dsk: DSK<'tasks, O, K>,
+    output: K,
+    /// The outer keys this task depends on, and the inner inputs their outputs are injected as
+    inputs: Vec<(K, K)>,
+    dependencies: Vec<K>,
+}
+
+impl<'tasks, O, K: Ord + Clone + 'tasks> SubGraph<'tasks, O, K> {
+    /// Wrap `dsk`, whose `output` task computes this task's output.
+    pub fn new(dsk: DSK<'tasks, O, K>, output: K) -> Self {
+        Self {
+            dsk,
+            output,
+            inputs: Vec::new(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// Make this task depend on `outer`, whose output is injected into the inner DSK as the input `inner`.
+    /// The input is added to the inner DSK if it doesn't have it yet.
+    pub fn input(mut self, outer: K, inner: K) -> Self {
+        self.dependencies.push(outer.clone());
+        self.inputs.push((outer, inner));
+        self
+    }
+
+    /// The wrapped DSK.
+    pub fn dsk(&self) -> &DSK<'tasks, O, K> {
+        &self.dsk
+    }
+}
+
+impl<'tasks, O: Clone, K: Ord + Clone + 'tasks> Task<O, K> for SubGraph<'tasks, O, K> {
+    fn execute(&mut self, cache: &BTreeMap<K, O>) -> Result<O, ExecuteError<K>> {
+        for (outer, inner) in &self.inputs {
+            let value = cache
+                .get(outer)
+                .cloned()
+                .ok_or_else(|| ExecuteError::MissingDependency(outer.clone()))?;
+
+            match self.dsk.0.contains_key(inner) {
+                true => self.dsk.set_input(inner.clone(), value)?,
+                false => self.dsk.add_input(inner.clone(), value)?,
+            }
+        }
+
+        self.dsk.execute(self.output.clone(), &mut BTreeMap::new())
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        Cow::Borrowed(&self.dependencies)
+    }
+}
@@ -2,7 +2,8 @@
 use super::*;
 use crate::cache::Cache;
 
-use alloc::boxed::Box;
+use alloc::{borrow::Cow, boxed::Box, collections::BTreeSet, format, string::String, vec::Vec};
+use core::cell::Cell;
 
 impl Task<()> for () {
     fn execute(&mut self, _: &BTreeMap<&str, ()>) -> Result<(), ExecuteError> {
@@ -16,24 +17,40 @@ impl Task<()> for DepTask {
     fn execute(&mut self, _: &BTreeMap<&str, ()>) -> Result<(), ExecuteError> {
         Ok(())
     }
-    fn dependencies(&self) -> &'static [&'static str] {
-        self.0
+    fn dependencies(&self) -> Dependencies<'_> {
+        Cow::Borrowed(self.0)
+    }
+}
+
+struct CountedTask<'a> {
+    deps: &'static [&'static str],
+    runs: &'a Cell<usize>,
+    f: fn(&BTreeMap<&str, usize>) -> usize,
+}
+
+impl<'a> Task<usize> for CountedTask<'a> {
+    fn execute(&mut self, cache: &BTreeMap<&str, usize>) -> Result<usize, ExecuteError> {
+        self.runs.set(self.runs.get() + 1);
+        Ok((self.f)(cache))
+    }
+    fn dependencies(&self) -> Dependencies<'_> {
+        Cow::Borrowed(self.deps)
     }
 }
 
 #[test]
 fn test_trait_semantics() {
     let mut d: Box<dyn Task<()>> = Box::new(());
-    let mut map = BTreeMap::new();
-    d.execute(&mut map).unwrap();
+    let map = BTreeMap::new();
+    d.execute(&map).unwrap();
 }
 
 #[test]
 fn test_closure_semantics() {
     let mut d = Cache::from_closure(|_| (0..10).sum::<usize>());
-    let mut map = BTreeMap::new();
+    let map = BTreeMap::<&str, _>::new();
 
-    let result = d.get(&mut map).unwrap();
+    let result = d.get(&map).unwrap();
     assert_eq!(*result, 45);
 }
 
@@ -102,3 +119,1558 @@ fn test_dsk_cull() {
     assert!(!culled.0.contains_key("A"));
     assert!(!culled.0.contains_key("Z"));
 }
+
+#[test]
+fn test_diamond_dependency_results() {
+    let runs = [Cell::new(0), Cell::new(0), Cell::new(0), Cell::new(0)];
+    let mut dsk = DSK::new();
+    let mut cache = BTreeMap::new();
+
+    dsk.add_task(
+        "A",
+        CountedTask {
+            deps: &[],
+            runs: &runs[0],
+            f: |_| 1,
+        },
+    )
+    .unwrap();
+    dsk.add_task(
+        "B",
+        CountedTask {
+            deps: &["A"],
+            runs: &runs[1],
+            f: |c| c["A"] + 10,
+        },
+    )
+    .unwrap();
+    dsk.add_task(
+        "C",
+        CountedTask {
+            deps: &["A"],
+            runs: &runs[2],
+            f: |c| c["A"] + 100,
+        },
+    )
+    .unwrap();
+    dsk.add_task(
+        "D",
+        CountedTask {
+            deps: &["B", "C"],
+            runs: &runs[3],
+            f: |c| c["B"] + c["C"],
+        },
+    )
+    .unwrap();
+
+    assert_eq!(dsk.execute("D", &mut cache).unwrap(), 112);
+    assert!(runs.iter().all(|r| r.get() == 1));
+
+    assert_eq!(cache.get("A"), Some(&1));
+    assert_eq!(cache.get("B"), Some(&11));
+    assert_eq!(cache.get("C"), Some(&101));
+    assert_eq!(cache.get("D"), None);
+}
+
+#[cfg(feature = "std")]
+struct FailingTask(&'static [&'static str]);
+
+#[cfg(feature = "std")]
+impl Task<usize> for FailingTask {
+    fn execute(&mut self, _: &BTreeMap<&str, usize>) -> Result<usize, ExecuteError> {
+        Err(ExecuteError::NullTask)
+    }
+    fn dependencies(&self) -> Dependencies<'_> {
+        Cow::Borrowed(self.0)
+    }
+}
+
+struct SumTask(&'static [&'static str], usize);
+
+impl Task<usize> for SumTask {
+    fn execute(&mut self, cache: &BTreeMap<&str, usize>) -> Result<usize, ExecuteError> {
+        Ok(self.0.iter().map(|dep| cache[dep]).sum::<usize>() + self.1)
+    }
+    fn dependencies(&self) -> Dependencies<'_> {
+        Cow::Borrowed(self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parallel_matches_sequential() {
+    let build = || {
+        let mut dsk = DSK::new();
+        dsk.add_send_task("A", SumTask(&[], 1)).unwrap();
+        dsk.add_send_task("B", SumTask(&["A"], 2)).unwrap();
+        dsk.add_send_task("C", SumTask(&["A"], 3)).unwrap();
+        dsk.add_send_task("D", SumTask(&["A", "C"], 4)).unwrap();
+        dsk.add_task("E", SumTask(&["B", "C", "D"], 5)).unwrap();
+        dsk
+    };
+
+    let (mut sequential, mut sequential_cache) = (build(), BTreeMap::new());
+    let (mut parallel, mut parallel_cache) = (build(), BTreeMap::new());
+
+    let expected = sequential.execute("E", &mut sequential_cache).unwrap();
+    let output = parallel
+        .execute_parallel("E", 4, &mut parallel_cache)
+        .unwrap();
+
+    assert_eq!(output, expected);
+    assert_eq!(parallel_cache, sequential_cache);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parallel_errors_match_sequential() {
+    let build = || {
+        let mut dsk = DSK::new();
+        dsk.add_send_task("A", SumTask(&[], 1)).unwrap();
+        dsk.add_send_task("B", FailingTask(&["A"])).unwrap();
+        dsk.add_send_task("C", SumTask(&["A", "M"], 3)).unwrap();
+        dsk.add_send_task("D", SumTask(&["C", "B"], 4)).unwrap();
+        dsk
+    };
+
+    let sequential = build().execute("D", &mut BTreeMap::new());
+    let parallel = build().execute_parallel("D", 4, &mut BTreeMap::new());
+
+    for result in [sequential, parallel] {
+        assert!(matches!(
+            result,
+            Err(ExecuteError::Context { path, error })
+                if path == ["D", "C"] && matches!(*error, ExecuteError::DanglingDependency("M", "C"))
+        ));
+    }
+}
+
+#[cfg(feature = "std")]
+struct PanickingTask;
+
+#[cfg(feature = "std")]
+impl Task<usize> for PanickingTask {
+    fn execute(&mut self, _: &BTreeMap<&str, usize>) -> Result<usize, ExecuteError> {
+        panic!("task panicked");
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parallel_task_panic() {
+    let mut dsk = DSK::new();
+    dsk.add_send_task("A", SumTask(&[], 1)).unwrap();
+    dsk.add_send_task("B", PanickingTask).unwrap();
+    dsk.add_send_task("C", SumTask(&["A", "B"], 1)).unwrap();
+
+    // The panic reaches the calling thread instead of leaving it waiting on the workers
+    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        dsk.execute_parallel("C", 2, &mut BTreeMap::new())
+    }));
+    let payload = outcome.unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"task panicked"));
+}
+
+fn block_on<F: core::future::Future>(future: F) -> F::Output {
+    let mut future = core::pin::pin!(future);
+    let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+
+    loop {
+        if let core::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Completes only once its partner has started, so it deadlocks unless both are polled concurrently
+struct Rendezvous<'a> {
+    started: &'a Cell<bool>,
+    partner: &'a Cell<bool>,
+    value: usize,
+}
+
+impl<'a> AsyncTask<usize> for Rendezvous<'a> {
+    fn execute<'f>(&'f mut self, _: &'f BTreeMap<&'f str, usize>) -> TaskFuture<'f, usize> {
+        Box::pin(async move {
+            self.started.set(true);
+            core::future::poll_fn(|cx| match self.partner.get() {
+                true => core::task::Poll::Ready(()),
+                false => {
+                    cx.waker().wake_by_ref();
+                    core::task::Poll::Pending
+                }
+            })
+            .await;
+
+            Ok(self.value)
+        })
+    }
+}
+
+#[test]
+fn test_async_execution() {
+    let (a, b) = (Cell::new(false), Cell::new(false));
+    let mut dsk = DSK::new();
+    let mut cache = BTreeMap::new();
+
+    dsk.add_async_task(
+        "A",
+        Rendezvous {
+            started: &a,
+            partner: &b,
+            value: 1,
+        },
+    )
+    .unwrap();
+    dsk.add_async_task(
+        "B",
+        Rendezvous {
+            started: &b,
+            partner: &a,
+            value: 2,
+        },
+    )
+    .unwrap();
+    dsk.add_async_task("C", Blocking(SumTask(&["A", "B"], 3)))
+        .unwrap();
+    dsk.add_task("D", SumTask(&["C"], 4)).unwrap();
+
+    assert_eq!(block_on(dsk.execute_async("D", &mut cache)).unwrap(), 10);
+    assert_eq!(cache.get("C"), Some(&6));
+
+    dsk.add_async_task("E", Blocking(SumTask(&["C"], 5)))
+        .unwrap();
+    assert!(matches!(
+        dsk.execute("E", &mut BTreeMap::new())
+            .unwrap_err()
+            .root_cause(),
+        ExecuteError::AsyncTask
+    ));
+}
+
+#[test]
+fn test_runtime_dependencies() {
+    let keys = (0..4)
+        .map(|i| format!("chunk-{i}"))
+        .collect::<Vec<String>>();
+    let mut dsk = DSK::new();
+    let mut cache = BTreeMap::new();
+
+    for (i, key) in keys.iter().enumerate() {
+        dsk.add_task(key.as_str(), move |_: &BTreeMap<&str, usize>| i)
+            .unwrap();
+    }
+    dsk.add_task(
+        "total",
+        WithDependencies::new(
+            keys.iter().map(String::as_str),
+            |c: &BTreeMap<&str, usize>| c.values().sum::<usize>(),
+        ),
+    )
+    .unwrap();
+    dsk.add_task(
+        "unused",
+        WithDependencies::new([keys[0].as_str()], |_: &BTreeMap<&str, usize>| 0),
+    )
+    .unwrap();
+
+    assert_eq!(
+        dsk.get_dependents("chunk-0"),
+        ["total", "unused"].into_iter().collect()
+    );
+
+    let mut dsk = dsk.cull(&["total"]).unwrap();
+    assert!(!dsk.0.contains_key("unused"));
+    assert_eq!(dsk.execute("total", &mut cache).unwrap(), 6);
+
+    let closure = Cache::<usize, &str>::from_closure_with_dependencies(["chunk-0"], |_| 0);
+    assert_eq!(closure.dependencies().as_ref(), ["chunk-0"]);
+}
+
+#[test]
+fn test_runtime_cyclic_dependencies() {
+    let (x, y) = (String::from("X"), String::from("Y"));
+    let mut dsk = DSK::<(), &str>::new();
+
+    // Tasks generic over the key type accept borrowed keys of any lifetime
+    dsk.add_task(x.as_str(), WithDependencies::new([y.as_str()], NullTask))
+        .unwrap();
+    assert!(dsk
+        .add_task(y.as_str(), WithDependencies::new([x.as_str()], NullTask))
+        .is_err());
+}
+
+#[test]
+fn test_default_keys() {
+    // `Task<O>` and `DSK<'tasks, O>` share their default key, however short lived the tasks are
+    fn add_counted<'tasks>(dsk: &mut DSK<'tasks, usize>, runs: &'tasks Cell<usize>) {
+        dsk.add_task(
+            "double",
+            CountedTask {
+                deps: &["source"],
+                runs,
+                f: |c| c["source"] * 2,
+            },
+        )
+        .unwrap();
+    }
+
+    let runs = Cell::new(0);
+    let mut dsk = DSK::new();
+    dsk.add_input("source", 2).unwrap();
+    add_counted(&mut dsk, &runs);
+    assert_eq!(dsk.execute("double", &mut BTreeMap::new()).unwrap(), 4);
+    assert_eq!(runs.get(), 1);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Stage {
+    Load,
+    Parse,
+    Report,
+}
+
+#[test]
+fn test_generic_keys() {
+    let mut dsk: DSK<usize, Stage> = DSK::new();
+    let mut cache = BTreeMap::new();
+
+    dsk.add_task(Stage::Load, |_: &BTreeMap<Stage, usize>| 2)
+        .unwrap();
+    dsk.add_task(
+        Stage::Parse,
+        WithDependencies::new([Stage::Load], |c: &BTreeMap<Stage, usize>| {
+            c[&Stage::Load] * 3
+        }),
+    )
+    .unwrap();
+    dsk.add_task(
+        Stage::Report,
+        WithDependencies::new([Stage::Parse], |c: &BTreeMap<Stage, usize>| {
+            c[&Stage::Parse] + 1
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(dsk.execute(Stage::Report, &mut cache).unwrap(), 7);
+    assert_eq!(cache[&Stage::Load], 2);
+
+    let mut owned: DSK<usize, String> = DSK::new();
+    owned
+        .add_task(String::from("a"), |_: &BTreeMap<String, usize>| 1)
+        .unwrap();
+    assert_eq!(owned.get_dependents("a"), BTreeSet::new());
+    assert!(matches!(
+        owned.add_task(String::from("a"), |_: &BTreeMap<String, usize>| 1),
+        Err(ExecuteError::TaskAlreadyExists(ref k)) if k == "a"
+    ));
+    assert!(matches!(
+        owned.execute(String::from("b"), &mut BTreeMap::new()),
+        Err(ExecuteError::MissingDependency(ref k)) if k == "b"
+    ));
+}
+
+const SOURCE: TaskKey<String> = TaskKey::new("source");
+const LENGTH: TaskKey<usize> = TaskKey::new("length");
+const SUMMARY: TaskKey<(String, usize)> = TaskKey::new("summary");
+
+#[test]
+fn test_typed_outputs() {
+    let mut dsk = DSK::new();
+    let mut cache = BTreeMap::new();
+
+    dsk.add_typed_task(&SOURCE, [], |_| Ok(String::from("tasker")))
+        .unwrap();
+    dsk.add_typed_task(&LENGTH, [SOURCE.key()], |inputs| {
+        Ok(inputs.get(&SOURCE)?.len())
+    })
+    .unwrap();
+    dsk.add_typed_task(&SUMMARY, [SOURCE.key(), LENGTH.key()], |inputs| {
+        Ok((inputs.get(&SOURCE)?.clone(), *inputs.get(&LENGTH)?))
+    })
+    .unwrap();
+
+    let summary = dsk.execute_typed(&SUMMARY, &mut cache).unwrap();
+    assert_eq!(*summary, (String::from("tasker"), 6));
+
+    const WRONG: TaskKey<u8> = TaskKey::new("length");
+    assert!(matches!(
+        dsk.execute_typed(&WRONG, &mut cache),
+        Err(ExecuteError::TypeMismatch("length", _))
+    ));
+
+    dsk.add_typed_task(&TaskKey::<u8>::new("misread"), [LENGTH.key()], |inputs| {
+        inputs.get(&WRONG).copied()
+    })
+    .unwrap();
+    assert!(matches!(
+        dsk.execute("misread", &mut cache),
+        Err(ExecuteError::Context { path, error })
+            if path == ["misread"] && matches!(*error, ExecuteError::TypeMismatch("length", _))
+    ));
+}
+
+#[test]
+fn test_invalidate() {
+    let runs = [Cell::new(0), Cell::new(0), Cell::new(0), Cell::new(0)];
+    let mut dsk = DSK::new();
+
+    dsk.add_task(
+        "A",
+        CountedTask {
+            deps: &[],
+            runs: &runs[0],
+            f: |_| 1,
+        },
+    )
+    .unwrap();
+    dsk.add_task(
+        "B",
+        CountedTask {
+            deps: &["A"],
+            runs: &runs[1],
+            f: |c| c["A"] + 1,
+        },
+    )
+    .unwrap();
+    dsk.add_task(
+        "C",
+        CountedTask {
+            deps: &[],
+            runs: &runs[2],
+            f: |_| 10,
+        },
+    )
+    .unwrap();
+    dsk.add_task(
+        "D",
+        CountedTask {
+            deps: &["B", "C"],
+            runs: &runs[3],
+            f: |c| c["B"] + c["C"],
+        },
+    )
+    .unwrap();
+
+    assert_eq!(dsk.execute("D", &mut BTreeMap::new()).unwrap(), 12);
+    assert!(dsk.0.values().all(Cache::is_computed));
+
+    let invalidated = dsk.invalidate("B");
+    assert_eq!(invalidated, ["B", "D"].into_iter().collect());
+    assert!(dsk.0["A"].is_computed() && dsk.0["C"].is_computed());
+    assert!(!dsk.0["B"].is_computed() && !dsk.0["D"].is_computed());
+
+    assert_eq!(dsk.execute("D", &mut BTreeMap::new()).unwrap(), 12);
+    let runs = runs.iter().map(Cell::get).collect::<Vec<_>>();
+    assert_eq!(runs, [1, 2, 1, 2]);
+
+    assert!(dsk.invalidate("missing").is_empty());
+
+    // Inputs keep their value, and only pass the invalidation on
+    let mut dsk = DSK::new();
+    dsk.add_input("a", 2).unwrap();
+    dsk.add_task("b", SumTask(&["a"], 1)).unwrap();
+    assert_eq!(dsk.execute("b", &mut BTreeMap::new()).unwrap(), 3);
+    assert_eq!(dsk.invalidate("a"), BTreeSet::from(["b"]));
+    assert_eq!(dsk.0["a"].result, Some(2));
+    assert_eq!(dsk.execute("b", &mut BTreeMap::new()).unwrap(), 3);
+}
+
+#[test]
+fn test_incremental_early_cutoff() {
+    let runs = [Cell::new(0), Cell::new(0)];
+    let mut dsk = DSK::new();
+
+    dsk.add_input("source", 3).unwrap();
+    dsk.add_task(
+        "parity",
+        CountedTask {
+            deps: &["source"],
+            runs: &runs[0],
+            f: |c| c["source"] % 2,
+        },
+    )
+    .unwrap();
+    dsk.add_task(
+        "report",
+        CountedTask {
+            deps: &["parity"],
+            runs: &runs[1],
+            f: |c| c["parity"] * 10,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        dsk.execute_incremental("report", &mut BTreeMap::new())
+            .unwrap(),
+        10
+    );
+    assert_eq!(dsk.revision(), 0);
+
+    // parity is unchanged, so report is not recomputed
+    dsk.set_input("source", 5).unwrap();
+    assert_eq!(dsk.revision(), 1);
+    assert_eq!(
+        dsk.execute_incremental("report", &mut BTreeMap::new())
+            .unwrap(),
+        10
+    );
+    assert_eq!((runs[0].get(), runs[1].get()), (2, 1));
+
+    dsk.set_input("source", 4).unwrap();
+    assert_eq!(
+        dsk.execute_incremental("report", &mut BTreeMap::new())
+            .unwrap(),
+        0
+    );
+    assert_eq!((runs[0].get(), runs[1].get()), (3, 2));
+
+    // Nothing changed, nothing is recomputed
+    assert_eq!(
+        dsk.execute_incremental("report", &mut BTreeMap::new())
+            .unwrap(),
+        0
+    );
+    assert_eq!((runs[0].get(), runs[1].get()), (3, 2));
+
+    // Without early cutoff every dependent of a changed input is recomputed
+    dsk.set_input("source", 6).unwrap();
+    assert_eq!(dsk.execute("report", &mut BTreeMap::new()).unwrap(), 0);
+    assert_eq!((runs[0].get(), runs[1].get()), (4, 3));
+
+    assert!(matches!(
+        dsk.set_input("parity", 1),
+        Err(ExecuteError::NotAnInput("parity"))
+    ));
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+#[test]
+fn test_persisted_results() {
+    let runs = [Cell::new(0), Cell::new(0)];
+    let build = |fingerprints: [u64; 2]| {
+        let mut dsk = DSK::new();
+        dsk.add_input("source", 3).unwrap();
+        dsk.add_task(
+            "double",
+            WithFingerprint::new(
+                fingerprints[0],
+                CountedTask {
+                    deps: &["source"],
+                    runs: &runs[0],
+                    f: |c| c["source"] * 2,
+                },
+            ),
+        )
+        .unwrap();
+        dsk.add_task(
+            "square",
+            WithFingerprint::new(
+                fingerprints[1],
+                CountedTask {
+                    deps: &["double"],
+                    runs: &runs[1],
+                    f: |c| c["double"] * c["double"],
+                },
+            ),
+        )
+        .unwrap();
+        dsk
+    };
+
+    let dir = std::env::temp_dir().join(format!("tasker-persist-{}", std::process::id()));
+    let file = dir.join("snapshot.json");
+
+    let mut dsk = build([1, 1]);
+    assert_eq!(dsk.execute("square", &mut BTreeMap::new()).unwrap(), 36);
+    let snapshot = dsk.snapshot().unwrap();
+    assert_eq!(snapshot.len(), 2);
+    snapshot.save_dir(&dir).unwrap();
+    snapshot.save(&file).unwrap();
+
+    // Everything is rehydrated, so nothing is recomputed
+    let mut dsk = build([1, 1]);
+    let restored = dsk.restore(&Snapshot::load(&file).unwrap()).unwrap();
+    assert_eq!(restored, BTreeSet::from(["double", "square"]));
+    assert_eq!(dsk.execute("square", &mut BTreeMap::new()).unwrap(), 36);
+    assert_eq!((runs[0].get(), runs[1].get()), (1, 1));
+
+    // A changed fingerprint discards the stored result, and recomputes the task
+    let mut dsk = build([1, 2]);
+    let restored = dsk.restore(&Snapshot::load_dir(&dir).unwrap()).unwrap();
+    assert_eq!(restored, BTreeSet::from(["double"]));
+    assert_eq!(dsk.execute("square", &mut BTreeMap::new()).unwrap(), 36);
+    assert_eq!((runs[0].get(), runs[1].get()), (1, 2));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_memoized_execution() {
+    let runs = [Cell::new(0), Cell::new(0)];
+    let build = |source: &'static str, input: usize| {
+        let mut dsk = DSK::new();
+        dsk.add_input(source, input).unwrap();
+        dsk.add_task(
+            "double",
+            WithFingerprint::new(
+                1,
+                WithDependencies::new(
+                    [source],
+                    CountedTask {
+                        deps: &[],
+                        runs: &runs[0],
+                        f: |c| c.values().sum::<usize>() * 2,
+                    },
+                ),
+            ),
+        )
+        .unwrap();
+        // Without a fingerprint, this task is never looked up
+        dsk.add_task(
+            "report",
+            CountedTask {
+                deps: &["double"],
+                runs: &runs[1],
+                f: |c| c["double"] + 1,
+            },
+        )
+        .unwrap();
+        dsk
+    };
+
+    let mut store = MemoryStore::new();
+    let mut hits = BTreeSet::new();
+    let mut dsk = build("a", 3);
+    assert_eq!(
+        dsk.execute_memoized("report", &mut BTreeMap::new(), &mut store, &mut hits)
+            .unwrap(),
+        7
+    );
+    assert!(hits.is_empty());
+    assert_eq!(store.len(), 1);
+
+    // The same computation, over the same input, is reused by another graph
+    let mut dsk = build("b", 3);
+    assert_eq!(
+        dsk.execute_memoized("report", &mut BTreeMap::new(), &mut store, &mut hits)
+            .unwrap(),
+        7
+    );
+    assert_eq!(hits, BTreeSet::from(["double"]));
+    assert_eq!((runs[0].get(), runs[1].get()), (1, 2));
+
+    // A different input is a different computation
+    hits.clear();
+    dsk.set_input("b", 4).unwrap();
+    assert_eq!(
+        dsk.execute_memoized("report", &mut BTreeMap::new(), &mut store, &mut hits)
+            .unwrap(),
+        9
+    );
+    assert!(hits.is_empty());
+    assert_eq!((runs[0].get(), runs[1].get()), (2, 3));
+    assert_eq!(store.len(), 2);
+
+    #[cfg(all(feature = "std", feature = "serde"))]
+    {
+        let dir = std::env::temp_dir().join(format!("tasker-memo-{}", std::process::id()));
+        let mut store = DiskStore::new(&dir).unwrap();
+        for expected_hits in [0, 1] {
+            hits.clear();
+            let mut dsk = build("c", 5);
+            assert_eq!(
+                dsk.execute_memoized("report", &mut BTreeMap::new(), &mut store, &mut hits)
+                    .unwrap(),
+                11
+            );
+            assert_eq!(hits.len(), expected_hits);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+#[test]
+fn test_dot_export() {
+    let mut dsk = DSK::new();
+    dsk.add_input("source", 1).unwrap();
+    dsk.add_task("double", |c: &BTreeMap<&str, usize>| c["source"] * 2)
+        .unwrap();
+    dsk.add_task(
+        "broken",
+        WithDependencies::new(["source", "missing"], |_: &BTreeMap<&str, usize>| 0),
+    )
+    .unwrap();
+
+    assert_eq!(
+        dsk.to_dot(),
+        "digraph {\n    \"broken\";\n    \"double\";\n    \"source\";\n    \"missing\" [style=\"dashed\" \
+         color=red fontcolor=red];\n    \"source\" -> \"broken\";\n    \"missing\" -> \"broken\" [color=red];\n}\n"
+    );
+
+    let options = ExportOptions {
+        computed: true,
+        errored: BTreeSet::from(["broken"]),
+        culled_to: Some(Vec::from(["source"])),
+    };
+    let dot = dsk.to_dot_with(&options);
+    assert!(dot.contains("\"source\" [style=\"filled\" fillcolor=palegreen];"));
+    assert!(dot.contains("\"double\" [style=\"dashed\" color=gray fontcolor=gray];"));
+    assert!(dot.contains(
+        "\"broken\" [style=\"filled,dashed\" fillcolor=salmon color=gray fontcolor=gray];"
+    ));
+}
+
+#[test]
+fn test_mermaid_export() {
+    let mut dsk = DSK::new();
+    dsk.add_input("source", 1).unwrap();
+    dsk.add_task(
+        "broken",
+        WithDependencies::new(["source", "missing"], |_: &BTreeMap<&str, usize>| 0),
+    )
+    .unwrap();
+
+    let options = ExportOptions {
+        computed: true,
+        errored: BTreeSet::from(["broken"]),
+        culled_to: None,
+    };
+    assert_eq!(
+        dsk.to_mermaid_with(&options),
+        "flowchart LR\n    n0[\"broken\"]\n    n1[\"source\"]\n    n2[\"missing\"]\n    n1 --> n0\n    n2 --> \
+         n0\n    classDef computed fill:#98fb98\n    class n1 computed\n    classDef errored \
+         fill:#fa8072\n    class n0 errored\n    classDef missing \
+         stroke:#f00,stroke-dasharray:5 5,color:#f00\n    class n2 missing\n    linkStyle 1 \
+         stroke:#f00\n"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_json_export_round_trip() {
+    let mut dsk = DSK::<usize, String>::new();
+    dsk.add_input("source".into(), 1).unwrap();
+    dsk.add_task(
+        "double".into(),
+        WithDependencies::new(["source"], |c: &BTreeMap<String, usize>| c["source"] * 2),
+    )
+    .unwrap();
+    dsk.execute("double".into(), &mut BTreeMap::new()).unwrap();
+
+    let export =
+        dsk.export_with(|key| (key == "double").then(|| serde_json::json!({"owner": "ci"})));
+    let json = export.to_json().unwrap();
+    assert_eq!(
+        json,
+        r#"{"version":1,"tasks":[{"key":"double","dependencies":["source"],"computed":true,"input":false,"metadata":{"owner":"ci"}},{"key":"source","dependencies":[],"computed":true,"input":true}]}"#
+    );
+    assert_eq!(GraphExport::from_json(&json).unwrap(), export);
+
+    let future = json.replacen("\"version\":1", "\"version\":2", 1);
+    assert!(GraphExport::<String>::from_json(&future).is_err());
+}
+
+#[test]
+fn test_validate() {
+    let mut dsk = DSK::new();
+    dsk.add_task("A", DepTask(&["B"])).unwrap();
+    dsk.add_task("Z", DepTask(&["A", "gone"])).unwrap();
+    assert!(dsk.validate().cycles.is_empty());
+
+    // Cycles can't be added through add_task, so they're inserted directly
+    for (key, deps) in [
+        ("B", &["C"][..]),
+        ("C", &["B", "missing"][..]),
+        ("D", &["D"][..]),
+        ("E", &["F", "missing"][..]),
+        ("F", &["G"][..]),
+        ("G", &["E", "B"][..]),
+    ] {
+        dsk.0.insert(key, Cache::from(DepTask(deps)));
+    }
+
+    let report = dsk.validate();
+    assert!(!report.is_valid());
+    assert_eq!(
+        report.cycles,
+        Vec::from([
+            Vec::from(["B", "C"]),
+            Vec::from(["D"]),
+            Vec::from(["E", "F", "G"])
+        ])
+    );
+    assert_eq!(
+        report.missing,
+        BTreeMap::from([
+            ("gone", BTreeSet::from(["Z"])),
+            ("missing", BTreeSet::from(["C", "E"]))
+        ])
+    );
+}
+
+#[test]
+fn test_builder() {
+    // Tasks may reference keys inserted after them
+    let mut dsk = DSKBuilder::new()
+        .add_task("sum", SumTask(&["a", "b"], 0))
+        .add_input("a", 1)
+        .add_tasks([("b", SumTask(&["a"], 10))])
+        .build()
+        .unwrap();
+    assert_eq!(dsk.execute("sum", &mut BTreeMap::new()).unwrap(), 12);
+
+    let duplicate = DSKBuilder::new()
+        .add_input("a", 1)
+        .add_input("a", 2)
+        .build();
+    assert!(matches!(
+        duplicate,
+        Err(ExecuteError::TaskAlreadyExists("a"))
+    ));
+
+    let invalid =
+        DSK::<(), &str>::try_from_iter([("X", DepTask(&["Y"])), ("Y", DepTask(&["X", "Z"]))]);
+    let Err(ExecuteError::InvalidGraph(report)) = invalid else {
+        panic!("expected an invalid graph");
+    };
+    assert_eq!(report.cycles, Vec::from([Vec::from(["X", "Y"])]));
+    assert_eq!(
+        report.missing,
+        BTreeMap::from([("Z", BTreeSet::from(["Y"]))])
+    );
+
+    let dsk = DSK::<(), &str>::try_from_iter([("X", DepTask(&["Y"])), ("Y", DepTask(&[]))]);
+    assert!(dsk.is_ok());
+}
+
+#[test]
+fn test_execution_plan() {
+    let mut dsk = DSK::new();
+    dsk.add_input("a", 1).unwrap();
+    dsk.add_input("b", 2).unwrap();
+    dsk.add_task("sum", SumTask(&["a", "b"], 0)).unwrap();
+    dsk.add_task("double", SumTask(&["sum", "sum"], 0)).unwrap();
+    dsk.add_task("offset", SumTask(&["a"], 10)).unwrap();
+    dsk.add_task("total", SumTask(&["double", "offset"], 0))
+        .unwrap();
+
+    let plan = dsk.plan(&["total", "sum"]).unwrap();
+    assert_eq!(
+        plan.order,
+        Vec::from(["a", "b", "sum", "double", "offset", "total"])
+    );
+    assert_eq!(plan.cached, BTreeSet::from(["a", "b"]));
+    assert_eq!(
+        plan.levels,
+        Vec::from([
+            Vec::from(["sum", "offset"]),
+            Vec::from(["double"]),
+            Vec::from(["total"])
+        ])
+    );
+    assert_eq!(
+        format!("{plan}"),
+        "targets: [\"total\", \"sum\"]\ncached: {\"a\", \"b\"}\nlevel 0: [\"sum\", \"offset\"]\nlevel 1: \
+         [\"double\"]\nlevel 2: [\"total\"]\n"
+    );
+
+    let outputs = dsk.run_plan(&plan, &mut BTreeMap::new()).unwrap();
+    assert_eq!(outputs, BTreeMap::from([("sum", 3), ("total", 17)]));
+
+    // Only what depends on the changed input is planned to run again
+    dsk.set_input("b", 5).unwrap();
+    let plan = dsk.plan(&["total"]).unwrap();
+    assert!(plan.computes(&"double") && !plan.computes(&"offset"));
+    assert_eq!(plan.levels.len(), 3);
+    assert_eq!(
+        dsk.run_plan(&plan, &mut BTreeMap::new()).unwrap()["total"],
+        23
+    );
+    assert!(dsk.plan(&["total"]).unwrap().levels.is_empty());
+
+    assert!(matches!(
+        dsk.plan(&["nothing"]),
+        Err(ExecuteError::MissingDependency("nothing"))
+    ));
+}
+
+#[test]
+fn test_deep_chain() {
+    const DEPTH: usize = 100_000;
+
+    let tasks = (1..DEPTH).map(|i| {
+        let task = move |c: &BTreeMap<usize, usize>| c[&(i - 1)] + 1;
+        (i, WithDependencies::new([i - 1], task))
+    });
+    let mut dsk = DSKBuilder::new()
+        .add_input(0, 0)
+        .add_tasks(tasks)
+        .build()
+        .unwrap();
+
+    dsk.check_cyclic_dependencies(&(DEPTH - 1)).unwrap();
+    assert_eq!(dsk.plan(&[DEPTH - 1]).unwrap().levels.len(), DEPTH - 1);
+
+    let mut cache = BTreeMap::new();
+    assert_eq!(dsk.execute(DEPTH - 1, &mut cache).unwrap(), DEPTH - 1);
+    assert_eq!(cache.len(), DEPTH - 1);
+    assert_eq!(dsk.invalidate(&1).len(), DEPTH - 1);
+
+    let mut dsk = dsk.cull(&[DEPTH / 2]).unwrap();
+    assert_eq!(dsk.0.len(), DEPTH / 2 + 1);
+    assert_eq!(
+        dsk.execute_incremental(DEPTH / 2, &mut cache).unwrap(),
+        DEPTH / 2
+    );
+}
+
+#[test]
+fn test_execute_many() {
+    let runs = Cell::new(0);
+    let mut dsk = DSK::new();
+    dsk.add_input("a", 2).unwrap();
+    dsk.add_task(
+        "shared",
+        CountedTask {
+            deps: &["a"],
+            runs: &runs,
+            f: |c| c["a"] * 10,
+        },
+    )
+    .unwrap();
+    dsk.add_task("left", SumTask(&["shared"], 1)).unwrap();
+    dsk.add_task("right", SumTask(&["shared", "a"], 0)).unwrap();
+
+    let outputs = dsk.execute_many(&["left", "right", "shared"]).unwrap();
+    assert_eq!(
+        outputs,
+        BTreeMap::from([("left", 21), ("right", 22), ("shared", 20)])
+    );
+    assert_eq!(runs.get(), 1);
+
+    dsk.add_task("broken", SumTask(&["missing"], 0)).unwrap();
+    assert!(matches!(
+        dsk.execute_many(&["left", "broken"]),
+        Err(ExecuteError::Context { path, error })
+            if path == ["broken"] && matches!(*error, ExecuteError::DanglingDependency("missing", "broken"))
+    ));
+}
+
+#[test]
+fn test_remove_replace_rename() {
+    let mut dsk = DSK::new();
+    dsk.add_input("a", 1).unwrap();
+    dsk.add_task("b", SumTask(&["a"], 1)).unwrap();
+    dsk.add_task("c", SumTask(&["b"], 1)).unwrap();
+    dsk.add_task("d", SumTask(&["e"], 1)).unwrap();
+    assert_eq!(dsk.execute("c", &mut BTreeMap::new()).unwrap(), 3);
+
+    // Replacing recomputes everything downstream
+    let invalidated = dsk.replace_task("b", SumTask(&["a"], 10)).unwrap();
+    assert_eq!(invalidated, BTreeSet::from(["b", "c"]));
+    assert_eq!(dsk.execute("c", &mut BTreeMap::new()).unwrap(), 12);
+
+    // A replacement closing a cycle is rejected, keeping the old task
+    assert!(matches!(
+        dsk.replace_task("b", SumTask(&["c"], 0)),
+        Err(ExecuteError::CyclicDependency(_))
+    ));
+    assert_eq!(dsk.0["b"].dependencies()[..], ["a"]);
+
+    // The replacement keeps the kind it's given, rather than becoming a local task
+    dsk.replace_async_task("b", Blocking(SumTask(&["a"], 10)))
+        .unwrap();
+    assert!(matches!(
+        dsk.execute("c", &mut BTreeMap::new())
+            .unwrap_err()
+            .root_cause(),
+        ExecuteError::AsyncTask
+    ));
+    assert_eq!(
+        block_on(dsk.execute_async("c", &mut BTreeMap::new())).unwrap(),
+        12
+    );
+    dsk.replace_send_task("b", SumTask(&["a"], 10)).unwrap();
+
+    assert!(matches!(
+        dsk.remove_task("b", OnDependents::Fail),
+        Err(ExecuteError::HasDependents("b", ref dependents)) if dependents == &["c"]
+    ));
+    assert!(dsk.0.contains_key("b"));
+
+    // Renaming "b" breaks "c", but resolves "d"'s missing dependency
+    assert_eq!(dsk.rename_task("b", "e").unwrap(), BTreeSet::from(["c"]));
+    assert_eq!(dsk.execute("d", &mut BTreeMap::new()).unwrap(), 12);
+    assert!(matches!(
+        dsk.execute("c", &mut BTreeMap::new())
+            .unwrap_err()
+            .root_cause(),
+        ExecuteError::DanglingDependency("b", "c")
+    ));
+    assert!(matches!(
+        dsk.rename_task("a", "d"),
+        Err(ExecuteError::TaskAlreadyExists("d"))
+    ));
+
+    // "e" depends on "a", so renaming "f" to "a" would close a cycle
+    dsk.remove_task("a", OnDependents::Break).unwrap();
+    dsk.add_task("f", SumTask(&["e"], 0)).unwrap();
+    assert!(matches!(
+        dsk.rename_task("f", "a"),
+        Err(ExecuteError::CyclicDependency(_))
+    ));
+    assert!(dsk.0.contains_key("f") && !dsk.0.contains_key("a"));
+
+    assert_eq!(
+        dsk.remove_task("e", OnDependents::Break).unwrap(),
+        BTreeSet::from(["d", "f"])
+    );
+
+    // A task added back under a removed or renamed key recomputes the tasks that depended on the old one
+    let mut dsk = DSK::new();
+    dsk.add_task("a", SumTask(&[], 1)).unwrap();
+    dsk.add_task("b", SumTask(&["a"], 0)).unwrap();
+    assert_eq!(dsk.execute("b", &mut BTreeMap::new()).unwrap(), 1);
+    dsk.remove_task("a", OnDependents::Break).unwrap();
+    dsk.add_task("a", SumTask(&[], 100)).unwrap();
+    assert_eq!(dsk.execute("b", &mut BTreeMap::new()).unwrap(), 100);
+    dsk.rename_task("a", "z").unwrap();
+    dsk.add_task("a", SumTask(&[], 1000)).unwrap();
+    assert_eq!(dsk.execute("b", &mut BTreeMap::new()).unwrap(), 1000);
+}
+
+#[test]
+fn test_merge() {
+    let build = |offset: usize| {
+        let mut dsk = DSK::new();
+        dsk.add_input("a", offset).unwrap();
+        dsk.add_task("b", SumTask(&["a"], offset)).unwrap();
+        dsk
+    };
+
+    let mut left = build(1);
+    left.add_task("c", SumTask(&["b"], 0)).unwrap();
+    assert_eq!(left.execute("c", &mut BTreeMap::new()).unwrap(), 2);
+
+    assert!(matches!(
+        left.merge(build(10), OnConflict::Fail),
+        Err(ExecuteError::TaskAlreadyExists("a"))
+    ));
+
+    left.merge(build(10), OnConflict::PreferLeft).unwrap();
+    assert_eq!(left.execute("c", &mut BTreeMap::new()).unwrap(), 2);
+
+    // Dependents of replaced tasks are recomputed
+    left.merge(build(10), OnConflict::PreferRight).unwrap();
+    assert_eq!(left.execute("c", &mut BTreeMap::new()).unwrap(), 20);
+
+    let mut cyclic = DSK::new();
+    cyclic.add_task("a", SumTask(&["c"], 0)).unwrap();
+    assert!(matches!(
+        left.merge(cyclic, OnConflict::PreferRight),
+        Err(ExecuteError::CyclicDependency(_))
+    ));
+    assert!(left.0["a"].is_input());
+
+    // Tasks merged in are recomputed from the tasks kept in their place
+    let mut left = DSK::new();
+    left.add_input("a", 1).unwrap();
+    let mut right = build(10);
+    right.add_task("d", SumTask(&["b"], 0)).unwrap();
+    assert_eq!(right.execute("d", &mut BTreeMap::new()).unwrap(), 20);
+    left.merge(right, OnConflict::PreferLeft).unwrap();
+    assert_eq!(left.execute("b", &mut BTreeMap::new()).unwrap(), 11);
+    assert_eq!(left.execute("d", &mut BTreeMap::new()).unwrap(), 11);
+}
+
+#[test]
+fn test_mount() {
+    let mut ingest = DSK::new();
+    ingest
+        .add_task(
+            "load",
+            WithDependencies::new(["/raw"], |c: &BTreeMap<&str, usize>| c["/raw"] * 2),
+        )
+        .unwrap();
+    ingest
+        .add_task(
+            "clean",
+            WithDependencies::new(["load"], |c: &BTreeMap<&str, usize>| c["load"] + 1),
+        )
+        .unwrap();
+    ingest.add_task("broken", SumTask(&["missing"], 0)).unwrap();
+
+    let mut dsk = DSK::<usize, String>::new();
+    dsk.add_input("raw".into(), 5).unwrap();
+    dsk.mount("ingest", ingest).unwrap();
+
+    assert_eq!(
+        dsk.0.keys().collect::<Vec<_>>(),
+        ["ingest/broken", "ingest/clean", "ingest/load", "raw"]
+    );
+    assert_eq!(dsk.0["ingest/clean"].dependencies()[..], ["ingest/load"]);
+    assert_eq!(
+        dsk.execute("ingest/clean".into(), &mut BTreeMap::new())
+            .unwrap(),
+        11
+    );
+    assert!(matches!(
+        dsk.execute("ingest/broken".into(), &mut BTreeMap::new()).unwrap_err().root_cause(),
+        ExecuteError::DanglingDependency(key, dependent)
+            if key == "ingest/missing" && dependent == "ingest/broken"
+    ));
+
+    let mut again = DSK::new();
+    again.add_input("load", 0).unwrap();
+    assert!(matches!(
+        dsk.mount("ingest", again),
+        Err(ExecuteError::TaskAlreadyExists(key)) if key == "ingest/load"
+    ));
+}
+
+#[test]
+fn test_subgraph() {
+    let inner = || {
+        let mut dsk = DSK::new();
+        dsk.add_task("double", SumTask(&["x", "x"], 0)).unwrap();
+        dsk.add_task("out", SumTask(&["double"], 1)).unwrap();
+        dsk.add_task("bad", NullTask).unwrap();
+        dsk.add_task("broken", SumTask(&["double", "bad"], 0))
+            .unwrap();
+        dsk
+    };
+
+    let mut dsk = DSK::new();
+    dsk.add_input("raw", 5).unwrap();
+    dsk.add_task("pipeline", SubGraph::new(inner(), "out").input("raw", "x"))
+        .unwrap();
+    dsk.add_task("total", SumTask(&["pipeline", "raw"], 0))
+        .unwrap();
+    assert_eq!(dsk.execute("total", &mut BTreeMap::new()).unwrap(), 16);
+
+    // The subgraph is recomputed as a unit when its dependencies change
+    dsk.set_input("raw", 1).unwrap();
+    assert_eq!(dsk.execute("total", &mut BTreeMap::new()).unwrap(), 4);
+
+    // Errors carry the path through every nested graph
+    let mut outer = DSK::new();
+    outer
+        .add_task("nested", SubGraph::new(inner(), "broken").input("raw", "x"))
+        .unwrap();
+    outer.add_task("wrapper", SumTask(&["nested"], 0)).unwrap();
+    dsk.add_task("deep", SubGraph::new(outer, "wrapper").input("raw", "raw"))
+        .unwrap();
+    assert!(matches!(
+        dsk.execute("deep", &mut BTreeMap::new()),
+        Err(ExecuteError::Context { path, error })
+            if path == ["deep", "wrapper", "nested", "broken", "bad"] && matches!(*error, ExecuteError::NullTask)
+    ));
+
+    let missing = SubGraph::new(inner(), "out");
+    dsk.add_task("missing", missing).unwrap();
+    assert!(matches!(
+        dsk.execute("missing", &mut BTreeMap::new()),
+        Err(ExecuteError::Context { path, error })
+            if path == ["missing", "out", "double"]
+                && matches!(*error, ExecuteError::DanglingDependency("x", "double"))
+    ));
+}
+
+#[test]
+fn test_task_errors() {
+    let mut dsk = DSK::new();
+    dsk.add_input("text", String::from("12")).unwrap();
+    dsk.add_task(
+        "parsed",
+        WithDependencies::new(
+            ["text"],
+            Fallible(|c: &BTreeMap<&str, String>| {
+                c["text"].parse::<usize>().map(|n| format!("{}", n * 2))
+            }),
+        ),
+    )
+    .unwrap();
+    assert_eq!(dsk.execute("parsed", &mut BTreeMap::new()).unwrap(), "24");
+
+    dsk.set_input("text", String::from("twelve")).unwrap();
+    let err = dsk.execute("parsed", &mut BTreeMap::new()).unwrap_err();
+
+    let ExecuteError::Failed(source) = err.root_cause() else {
+        panic!("expected the task's own error");
+    };
+    assert!(source.downcast_ref::<core::num::ParseIntError>().is_some());
+    assert_eq!(
+        format!("{err}"),
+        "Task failed: invalid digit found in string (at [\"parsed\"])"
+    );
+
+    // The source chain leads from the error wrapped in its context, down to the task's own error
+    let failed = core::error::Error::source(&err).unwrap();
+    assert!(failed.is::<ExecuteError>());
+    assert!(failed.source().unwrap().is::<core::num::ParseIntError>());
+}
+
+#[test]
+fn test_error_context() {
+    let mut dsk = DSK::new();
+    dsk.add_input("a", 1).unwrap();
+    dsk.add_task("bad", WithDependencies::new(["a"], NullTask))
+        .unwrap();
+    dsk.add_task("mid", SumTask(&["a", "bad"], 0)).unwrap();
+    dsk.add_task("top", SumTask(&["a", "mid"], 0)).unwrap();
+    dsk.add_task("other", SumTask(&["bad"], 0)).unwrap();
+
+    let err = dsk.execute("top", &mut BTreeMap::new()).unwrap_err();
+    assert_eq!(err.path(), Some(&["top", "mid", "bad"][..]));
+    assert!(matches!(err.root_cause(), ExecuteError::NullTask));
+    assert_eq!(
+        format!("{err}"),
+        "Attempted to execute a NULL Task (at [\"top\", \"mid\", \"bad\"])"
+    );
+
+    // The path starts from the first target needing the failing task
+    let err = dsk.execute_many(&["other", "top"]).unwrap_err();
+    assert_eq!(err.path(), Some(&["other", "bad"][..]));
+
+    let plan = dsk.plan(&["top"]).unwrap();
+    let err = dsk.run_plan(&plan, &mut BTreeMap::new()).unwrap_err();
+    assert_eq!(err.path(), Some(&["top", "mid", "bad"][..]));
+
+    // A missing dependency names the task declaring it
+    dsk.add_task("dangling", SumTask(&["a", "gone"], 0))
+        .unwrap();
+    dsk.add_task("root", SumTask(&["dangling"], 0)).unwrap();
+    let err = dsk.execute("root", &mut BTreeMap::new()).unwrap_err();
+    assert_eq!(err.path(), Some(&["root", "dangling"][..]));
+    assert!(matches!(
+        err.root_cause(),
+        ExecuteError::DanglingDependency("gone", "dangling")
+    ));
+    assert!(matches!(
+        dsk.plan(&["root"]),
+        Err(ExecuteError::Context { error, .. })
+            if matches!(*error, ExecuteError::DanglingDependency("gone", "dangling"))
+    ));
+
+    // Executing a key that doesn't exist has no path to report
+    assert!(matches!(
+        dsk.execute("nowhere", &mut BTreeMap::new()),
+        Err(ExecuteError::MissingDependency("nowhere"))
+    ));
+}
+
+#[test]
+fn test_keep_going() {
+    let mut dsk = DSK::new();
+    dsk.add_input("a", 1).unwrap();
+    dsk.add_task("bad", WithDependencies::new(["a"], NullTask))
+        .unwrap();
+    dsk.add_task("after", SumTask(&["bad"], 0)).unwrap();
+    dsk.add_task("last", SumTask(&["a", "after"], 0)).unwrap();
+    dsk.add_task("sibling", SumTask(&["a"], 2)).unwrap();
+    dsk.add_task("dangling", SumTask(&["gone"], 0)).unwrap();
+
+    let report = dsk.execute_keep_going(&["last", "sibling", "dangling", "nowhere"]);
+    assert!(!report.is_success());
+    assert_eq!(report.output(&"a"), Some(&1));
+    assert_eq!(report.output(&"sibling"), Some(&3));
+    assert!(matches!(
+        report.outcomes[&"bad"],
+        Outcome::Err(ExecuteError::Context { ref path, .. }) if path == &["last", "after", "bad"]
+    ));
+    assert!(matches!(report.outcomes[&"after"], Outcome::Skipped("bad")));
+    assert!(matches!(report.outcomes[&"last"], Outcome::Skipped("bad")));
+    assert!(matches!(
+        report.outcomes[&"dangling"],
+        Outcome::Err(ref err) if matches!(err.root_cause(), ExecuteError::DanglingDependency("gone", "dangling"))
+    ));
+    assert!(matches!(
+        report.outcomes[&"nowhere"],
+        Outcome::Err(ExecuteError::MissingDependency("nowhere"))
+    ));
+    assert_eq!(
+        report.errors().map(|(key, _)| *key).collect::<Vec<_>>(),
+        ["bad", "dangling", "nowhere"]
+    );
+
+    // Successful results stay cached
+    assert_eq!(dsk.0["sibling"].result, Some(3));
+
+    let report = dsk.execute_keep_going(&["sibling"]);
+    assert!(report.is_success());
+    assert_eq!(report.outcomes.len(), 2);
+}
+
+/// Fails with a parse error until it has run `succeeds_at` times
+fn flaky(runs: &Cell<u32>, succeeds_at: u32) -> impl Task<usize> + '_ {
+    Fallible(move |_: &BTreeMap<&str, usize>| {
+        runs.set(runs.get() + 1);
+        match runs.get() >= succeeds_at {
+            true => Ok(runs.get() as usize),
+            false => "".parse::<usize>(),
+        }
+    })
+}
+
+#[test]
+fn test_retries() {
+    use core::time::Duration;
+
+    let (recovers, exhausted, rejected, once) =
+        (Cell::new(0), Cell::new(0), Cell::new(0), Cell::new(0));
+    let (kept, dropped) = (Cell::new(0), Cell::new(0));
+    let mut dsk = DSK::new();
+    let retry = |attempts| TaskOptions {
+        retry: RetryPolicy::new(attempts).backoff(Duration::ZERO),
+        ..TaskOptions::default()
+    };
+    dsk.add_task_with("recovers", flaky(&recovers, 3), retry(3))
+        .unwrap();
+    dsk.add_task_with("exhausted", flaky(&exhausted, 3), retry(2))
+        .unwrap();
+    dsk.add_task_with(
+        "rejected",
+        flaky(&rejected, 3),
+        TaskOptions {
+            retry: RetryPolicy::new(3).retry_if(|err| !err.is::<core::num::ParseIntError>()),
+            ..TaskOptions::default()
+        },
+    )
+    .unwrap();
+    dsk.add_task("once", flaky(&once, 3)).unwrap();
+    dsk.add_task_with("null", NullTask, retry(3)).unwrap();
+
+    let report = dsk.execute_keep_going(&["recovers", "exhausted", "rejected", "once", "null"]);
+    assert_eq!(report.output(&"recovers"), Some(&3));
+    assert_eq!(
+        report.errors().map(|(key, _)| *key).collect::<Vec<_>>(),
+        ["exhausted", "null", "once", "rejected"]
+    );
+    // Only task-defined errors are retried
+    assert_eq!(
+        report.attempts,
+        BTreeMap::from([
+            ("exhausted", 2),
+            ("null", 1),
+            ("once", 1),
+            ("recovers", 3),
+            ("rejected", 1)
+        ])
+    );
+    assert_eq!(exhausted.get(), 2);
+
+    // A cached result doesn't run again
+    let report = dsk.execute_keep_going(&["recovers"]);
+    assert!(report.is_success());
+    assert!(report.attempts.is_empty());
+
+    // A replaced task keeps its options, unless given new ones
+    dsk.replace_task("exhausted", flaky(&kept, 2)).unwrap();
+    dsk.replace_task_with("rejected", flaky(&dropped, 2), TaskOptions::default())
+        .unwrap();
+    let report = dsk.execute_keep_going(&["exhausted", "rejected"]);
+    assert_eq!(report.output(&"exhausted"), Some(&2));
+    assert_eq!(
+        report.attempts,
+        BTreeMap::from([("exhausted", 2), ("rejected", 1)])
+    );
+
+    let backoff = Exponential {
+        initial: Duration::from_millis(10),
+        max: Duration::from_millis(50),
+    };
+    assert_eq!(
+        (1..=4)
+            .map(|attempt| backoff.delay(attempt))
+            .collect::<Vec<_>>(),
+        [10, 20, 40, 50].map(Duration::from_millis)
+    );
+    assert_eq!(Duration::from_millis(5).delay(7), Duration::from_millis(5));
+
+    // Async tasks are retried too, once given options
+    let runs = Cell::new(0);
+    let mut dsk = DSK::new();
+    dsk.add_async_task("flaky", Blocking(flaky(&runs, 2)))
+        .unwrap();
+    dsk.set_options("flaky", retry(2)).unwrap();
+    assert_eq!(
+        block_on(dsk.execute_async("flaky", &mut BTreeMap::new())).unwrap(),
+        2
+    );
+    assert_eq!(dsk.0["flaky"].revisions.attempts, 2);
+
+    #[cfg(feature = "std")]
+    {
+        let runs = Cell::new(0);
+        let mut dsk = DSK::new();
+        let options = TaskOptions {
+            retry: RetryPolicy::new(2).backoff(|_| Duration::from_millis(1)),
+            ..TaskOptions::default()
+        };
+        dsk.add_task_with("flaky", flaky(&runs, 2), options)
+            .unwrap();
+        dsk.add_task("sum", SumTask(&["flaky"], 1)).unwrap();
+        assert_eq!(
+            dsk.execute_parallel("sum", 2, &mut BTreeMap::new())
+                .unwrap(),
+            3
+        );
+        assert_eq!(dsk.0["flaky"].revisions.attempts, 2);
+
+        // Polling a future mustn't block, so sync tasks don't wait between attempts either
+        let runs = Cell::new(0);
+        let mut dsk = DSK::new();
+        let options = TaskOptions {
+            retry: RetryPolicy::new(2).backoff(Duration::from_secs(60)),
+            ..TaskOptions::default()
+        };
+        dsk.add_task_with("flaky", flaky(&runs, 2), options)
+            .unwrap();
+        let start = std::time::Instant::now();
+        assert_eq!(
+            block_on(dsk.execute_async("flaky", &mut BTreeMap::new())).unwrap(),
+            2
+        );
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
+
+#[test]
+fn test_fallbacks() {
+    let fails = |_: &BTreeMap<&str, usize>| "".parse::<usize>();
+    let recovers = Cell::new(0);
+
+    let mut dsk = DSK::new();
+    dsk.add_input("base", 1).unwrap();
+    dsk.add_task_with(
+        "enrich",
+        WithDependencies::new(["base"], Fallible(fails)),
+        TaskOptions {
+            fallback: Some(Fallback::value(0)),
+            ..TaskOptions::default()
+        },
+    )
+    .unwrap();
+    dsk.add_task_with(
+        "estimate",
+        WithDependencies::new(["base"], Fallible(fails)),
+        TaskOptions {
+            retry: RetryPolicy::new(2),
+            fallback: Some(Fallback::task(|c: &BTreeMap<&str, usize>| c["base"] + 100)),
+        },
+    )
+    .unwrap();
+    dsk.add_task_with(
+        "hopeless",
+        Fallible(fails),
+        TaskOptions {
+            fallback: Some(Fallback::task(NullTask)),
+            ..TaskOptions::default()
+        },
+    )
+    .unwrap();
+    dsk.add_task("total", SumTask(&["base", "enrich", "estimate"], 0))
+        .unwrap();
+
+    let report = dsk.execute_keep_going(&["total", "hopeless"]);
+    assert_eq!(report.output(&"total"), Some(&102));
+    assert_eq!(report.fallbacks, BTreeSet::from(["enrich", "estimate"]));
+    assert_eq!(report.attempts[&"estimate"], 2);
+    // A failing fallback leaves the task's own error
+    assert!(matches!(
+        report.outcomes[&"hopeless"],
+        Outcome::Err(ref err) if matches!(err.root_cause(), ExecuteError::Failed(_))
+    ));
+
+    // The fallback's output only stands in until the next execution, which tries the task itself again
+    assert_eq!(dsk.execute("enrich", &mut BTreeMap::new()).unwrap(), 0);
+    let report = dsk.execute_keep_going(&["total"]);
+    assert_eq!(
+        report.attempts,
+        BTreeMap::from([("enrich", 1), ("estimate", 2)])
+    );
+    assert_eq!(report.fallbacks, BTreeSet::from(["enrich", "estimate"]));
+
+    dsk.add_task_with(
+        "recovers",
+        flaky(&recovers, 2),
+        TaskOptions {
+            fallback: Some(Fallback::value(0)),
+            ..TaskOptions::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(dsk.execute("recovers", &mut BTreeMap::new()).unwrap(), 0);
+    assert_eq!(dsk.execute("recovers", &mut BTreeMap::new()).unwrap(), 2);
+    assert!(!dsk.0["recovers"].revisions.fallback);
+
+    // Mounted fallback tasks read the namespaced outputs of their task's dependencies
+    let mut inner = DSK::new();
+    inner
+        .add_task_with(
+            "guess",
+            WithDependencies::new(["/base"], Fallible(fails)),
+            TaskOptions {
+                fallback: Some(Fallback::task(|c: &BTreeMap<&str, usize>| c["/base"] * 3)),
+                ..TaskOptions::default()
+            },
+        )
+        .unwrap();
+    let mut outer = DSK::<usize, String>::new();
+    outer.add_input("base".into(), 4).unwrap();
+    outer.mount("inner", inner).unwrap();
+    assert_eq!(
+        outer
+            .execute("inner/guess".into(), &mut BTreeMap::new())
+            .unwrap(),
+        12
+    );
+
+    // A fallback's output is never remembered as the task's own
+    let mut dsk = DSK::new();
+    dsk.add_input("base", 1).unwrap();
+    dsk.add_task_with(
+        "enrich",
+        WithFingerprint::new(1, WithDependencies::new(["base"], Fallible(fails))),
+        TaskOptions {
+            fallback: Some(Fallback::value(0)),
+            ..TaskOptions::default()
+        },
+    )
+    .unwrap();
+    let mut store = MemoryStore::new();
+    let mut hits = BTreeSet::new();
+    assert_eq!(
+        dsk.execute_memoized("enrich", &mut BTreeMap::new(), &mut store, &mut hits)
+            .unwrap(),
+        0
+    );
+    assert!(store.is_empty());
+    #[cfg(all(feature = "std", feature = "serde"))]
+    assert!(dsk.snapshot().unwrap().is_empty());
+
+    #[cfg(feature = "std")]
+    {
+        let mut dsk = DSK::new();
+        dsk.add_input("base", 2).unwrap();
+        dsk.add_task_with(
+            "enrich",
+            WithDependencies::new(["base"], Fallible(fails)),
+            TaskOptions {
+                fallback: Some(Fallback::value(7)),
+                ..TaskOptions::default()
+            },
+        )
+        .unwrap();
+        dsk.add_send_task("total", SumTask(&["base", "enrich"], 0))
+            .unwrap();
+        assert_eq!(
+            dsk.execute_parallel("total", 2, &mut BTreeMap::new())
+                .unwrap(),
+            9
+        );
+        assert!(dsk.0["enrich"].revisions.fallback);
+    }
+}
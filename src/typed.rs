@@ -0,0 +1,116 @@
+use crate::{Dependencies, ExecuteError, Task, DSK};
+use alloc::{borrow::Cow, collections::BTreeMap, rc::Rc, vec::Vec};
+use core::{any::Any, fmt, marker::PhantomData};
+
+/// The type-erased output stored by a typed DSK.
+pub type AnyOutput = Rc<dyn Any>;
+
+/// A typed handle to a task whose output is a `T`.
+pub struct TaskKey<T, K = &'static str> {
+    key: K,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T, K> TaskKey<T, K> {
+    /// Create a handle to the task stored under `key`.
+    pub const fn new(key: K) -> Self {
+        Self {
+            key,
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<T, K: Clone> TaskKey<T, K> {
+    /// The untyped key this handle refers to.
+    pub fn key(&self) -> K {
+        self.key.clone()
+    }
+}
+
+impl<T, K: Clone> Clone for TaskKey<T, K> {
+    fn clone(&self) -> Self {
+        Self::new(self.key.clone())
+    }
+}
+
+impl<T, K: fmt::Debug> fmt::Debug for TaskKey<T, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TaskKey").field(&self.key).finish()
+    }
+}
+
+/// The outputs of a typed task's dependencies, retrieved through their [`TaskKey`]s.
+pub struct Inputs<'a, K>(&'a BTreeMap<K, AnyOutput>);
+
+impl<'a, K: Ord + Clone> Inputs<'a, K> {
+    /// Get the output of the dependency behind `key`.
+    pub fn get<T: 'static>(&self, key: &TaskKey<T, K>) -> Result<&'a T, ExecuteError<K>> {
+        self.0
+            .get(&key.key)
+            .ok_or_else(|| ExecuteError::MissingDependency(key.key()))?
+            .downcast_ref()
+            .ok_or_else(|| ExecuteError::TypeMismatch(key.key(), core::any::type_name::<T>()))
+    }
+}
+
+/// A Task producing a `T`, whose output is stored type-erased in a DSK.
+pub struct TypedTask<T, K, F> {
+    dependencies: Vec<K>,
+    f: F,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T, K, F> TypedTask<T, K, F> {
+    /// Create a typed task from a closure, depending on the given keys.
+    pub fn new(dependencies: impl IntoIterator<Item = K>, f: F) -> Self {
+        Self {
+            dependencies: dependencies.into_iter().collect(),
+            f,
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<T, K, F> Task<AnyOutput, K> for TypedTask<T, K, F>
+where
+    T: 'static,
+    K: Ord + Clone,
+    F: Fn(&Inputs<'_, K>) -> Result<T, ExecuteError<K>>,
+{
+    fn execute(&mut self, cache: &BTreeMap<K, AnyOutput>) -> Result<AnyOutput, ExecuteError<K>> {
+        Ok(Rc::new((self.f)(&Inputs(cache))?))
+    }
+
+    fn dependencies(&self) -> Dependencies<'_, K> {
+        Cow::Borrowed(&self.dependencies)
+    }
+}
+
+impl<'tasks, K: Ord + Clone + 'tasks> DSK<'tasks, AnyOutput, K> {
+    /// Adds a task producing a `T` under the typed handle `key`
+    pub fn add_typed_task<T, F>(
+        &mut self,
+        key: &TaskKey<T, K>,
+        dependencies: impl IntoIterator<Item = K>,
+        f: F,
+    ) -> Result<(), ExecuteError<K>>
+    where
+        T: 'static,
+        F: Fn(&Inputs<'_, K>) -> Result<T, ExecuteError<K>> + 'tasks,
+    {
+        self.add_task(key.key(), TypedTask::new(dependencies, f))
+    }
+
+    /// Executes the task behind `key`, like `execute`, returning its output as a `T`
+    pub fn execute_typed<T: 'static>(
+        &mut self,
+        key: &TaskKey<T, K>,
+        // ==+== ==+== ==+== //
+        cache: &mut BTreeMap<K, AnyOutput>,
+    ) -> Result<Rc<T>, ExecuteError<K>> {
+        self.execute(key.key(), cache)?
+            .downcast()
+            .map_err(|_| ExecuteError::TypeMismatch(key.key(), core::any::type_name::<T>()))
+    }
+}
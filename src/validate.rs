@@ -0,0 +1,140 @@
+use crate::DSK;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+
+/// Everything wrong with a DSK's structure, as found by `DSK::validate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport<K> {
+    /// Every group of tasks that depend on each other in a circle, each sorted by key.
+    /// A group is a strongly connected component, so it holds every task on any of the cycles through it.
+    pub cycles: Vec<Vec<K>>,
+    /// Every key depended upon but missing from the graph, along with the tasks depending on it.
+    pub missing: BTreeMap<K, BTreeSet<K>>,
+}
+
+impl<K> Default for ValidationReport<K> {
+    fn default() -> Self {
+        Self {
+            cycles: Vec::new(),
+            missing: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K> ValidationReport<K> {
+    /// Whether the graph has neither cycles nor missing dependencies.
+    pub fn is_valid(&self) -> bool {
+        self.cycles.is_empty() && self.missing.is_empty()
+    }
+}
+
+impl<'tasks, O, K: Ord + Clone + 'tasks> DSK<'tasks, O, K> {
+    /// Checks the whole graph for cycles and missing dependencies, reporting all of them at once.
+    /// Runs in time linear in the number of tasks and dependencies, using Tarjan's strongly connected components.
+    pub fn validate(&self) -> ValidationReport<K> {
+        let keys = self.0.keys().collect::<Vec<_>>();
+        let index = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (*key, i))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut report = ValidationReport::default();
+        let mut edges = Vec::with_capacity(keys.len());
+        for (key, task) in self.0.iter() {
+            let mut adjacent = Vec::new();
+            for dep in task.dependencies().iter() {
+                match index.get(dep) {
+                    Some(i) => adjacent.push(*i),
+                    None => {
+                        report
+                            .missing
+                            .entry(dep.clone())
+                            .or_insert_with(BTreeSet::new)
+                            .insert(key.clone());
+                    }
+                }
+            }
+            edges.push(adjacent);
+        }
+
+        for mut component in strongly_connected(&edges) {
+            let cyclic = component.len() > 1 || edges[component[0]].contains(&component[0]);
+            if cyclic {
+                component.sort_unstable();
+                report
+                    .cycles
+                    .push(component.into_iter().map(|i| keys[i].clone()).collect());
+            }
+        }
+
+        report.cycles.sort();
+        report
+    }
+}
+
+/// Tarjan's strongly connected components, over nodes `0..edges.len()`.
+/// Iterative, so arbitrarily deep graphs can't overflow the stack.
+fn strongly_connected(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let len = edges.len();
+    let mut index = vec![usize::MAX; len];
+    let mut low = vec![0; len];
+    let mut on_stack = vec![false; len];
+    let mut stack = Vec::new();
+    let mut next = 0;
+    let mut components = Vec::new();
+
+    for root in 0..len {
+        if index[root] != usize::MAX {
+            continue;
+        }
+
+        // Each frame holds a node, and how many of its edges were followed so far
+        let mut frames = vec![(root, 0)];
+        index[root] = next;
+        low[root] = next;
+        next += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((node, followed)) = frames.last_mut() {
+            let node = *node;
+            if let Some(&dep) = edges[node].get(*followed) {
+                *followed += 1;
+                if index[dep] == usize::MAX {
+                    index[dep] = next;
+                    low[dep] = next;
+                    next += 1;
+                    stack.push(dep);
+                    on_stack[dep] = true;
+                    frames.push((dep, 0));
+                } else if on_stack[dep] {
+                    low[node] = low[node].min(index[dep]);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some((parent, _)) = frames.last() {
+                low[*parent] = low[*parent].min(low[node]);
+            }
+
+            if low[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}